@@ -0,0 +1,9 @@
+#![no_main]
+
+use aoc::days::day1;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = day1::solve_part_1(Some(input.to_string()));
+    let _ = day1::solve_part_2(Some(input.to_string()));
+});
@@ -0,0 +1,261 @@
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::process::Command;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::days::{self, Part, SolveOptions};
+
+pub static DEFAULT_HISTORY_PATH: &str = ".aoc-bench-history";
+pub static DEFAULT_ITERATIONS: usize = 100;
+
+// A run is only reported as a regression if it is both statistically significant (Welch's
+// t-test, roughly 99.5% one-sided for the sample sizes we use) and large enough to care about.
+static T_STATISTIC_THRESHOLD: f64 = 2.58;
+static MINIMUM_SLOWDOWN: f64 = 0.05;
+static BASELINE_RUNS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkRun {
+    pub timestamp: u64,
+    pub day: u8,
+    pub part: Part,
+    pub input_hash: u64,
+    pub revision: Option<String>,
+    pub timings: Vec<u64>,
+}
+
+impl BenchmarkRun {
+    pub fn mean(&self) -> f64 {
+        if self.timings.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self.timings.iter().map(|timing| *timing as f64).sum();
+        return total / self.timings.len() as f64;
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.timings.len() < 2 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let squared_deviations: f64 = self
+            .timings
+            .iter()
+            .map(|timing| (*timing as f64 - mean).powi(2))
+            .sum();
+        return squared_deviations / (self.timings.len() - 1) as f64;
+    }
+
+    pub fn standard_deviation(&self) -> f64 {
+        return self.variance().sqrt();
+    }
+}
+
+#[derive(Debug)]
+pub struct Comparison {
+    pub baseline_revision: Option<String>,
+    pub change: f64,
+    pub t_statistic: f64,
+    pub is_regression: bool,
+}
+
+pub fn get_git_revision() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "--short", "HEAD"]).output();
+    return match output {
+        Ok(output) if output.status.success() => {
+            let revision = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if revision.is_empty() {
+                None
+            } else {
+                Some(revision)
+            }
+        }
+        _ => None,
+    };
+}
+
+fn get_timestamp() -> u64 {
+    return match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs(),
+        Err(_) => 0,
+    };
+}
+
+pub fn run_benchmark(
+    day: u8,
+    part: Part,
+    input: Option<String>,
+    iterations: usize,
+    options: &SolveOptions,
+) -> Result<BenchmarkRun, String> {
+    if iterations == 0 {
+        return Err("Iterations must be greater than zero".to_string());
+    }
+
+    let input_result = days::get_input_text(day, input);
+    if input_result.is_err() {
+        return Err(input_result.err().unwrap());
+    }
+    let input_text = input_result.unwrap();
+
+    // warm up, and make sure the solver succeeds before timing it
+    let warm_up_result = days::solve_with_options(day, part, Some(input_text.clone()), options);
+    if warm_up_result.is_err() {
+        return Err(warm_up_result.err().unwrap());
+    }
+
+    let mut timings = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let iteration_input = input_text.clone();
+        let start = Instant::now();
+        let result = days::solve_with_options(day, part, Some(iteration_input), options);
+        let elapsed = start.elapsed();
+        if result.is_err() {
+            return Err(result.err().unwrap());
+        }
+        timings.push(elapsed.as_nanos() as u64);
+    }
+
+    return Ok(BenchmarkRun {
+        timestamp: get_timestamp(),
+        day,
+        part,
+        // variants hash their options with the input, so they are compared with their own runs
+        input_hash: days::hash_variant_input(&input_text, options),
+        revision: get_git_revision(),
+        timings,
+    });
+}
+
+fn format_history_line(run: &BenchmarkRun) -> String {
+    let timings: Vec<String> = run.timings.iter().map(|timing| timing.to_string()).collect();
+    return format!(
+        "{}\t{}\t{}\t{:016x}\t{}\t{}",
+        run.timestamp,
+        run.day,
+        run.part.number(),
+        run.input_hash,
+        run.revision.clone().unwrap_or_else(|| "-".to_string()),
+        timings.join(",")
+    );
+}
+
+fn parse_history_line(line: &str) -> Result<BenchmarkRun, String> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    if fields.len() != 6 {
+        return Err(format!("Invalid benchmark history entry: '{}'", line));
+    }
+
+    let timestamp = fields[0].parse::<u64>();
+    let day = fields[1].parse::<u8>();
+    let part = match fields[2] {
+        "1" => Some(Part::Part1),
+        "2" => Some(Part::Part2),
+        _ => None,
+    };
+    let input_hash = u64::from_str_radix(fields[3], 16);
+    let revision = match fields[4] {
+        "-" => None,
+        revision => Some(revision.to_string()),
+    };
+    let timings: Result<Vec<u64>, _> = fields[5].split(',').map(|timing| timing.parse::<u64>()).collect();
+
+    if timestamp.is_err() || day.is_err() || part.is_none() || input_hash.is_err() || timings.is_err() {
+        return Err(format!("Invalid benchmark history entry: '{}'", line));
+    }
+
+    return Ok(BenchmarkRun {
+        timestamp: timestamp.unwrap(),
+        day: day.unwrap(),
+        part: part.unwrap(),
+        input_hash: input_hash.unwrap(),
+        revision,
+        timings: timings.unwrap(),
+    });
+}
+
+// Lines that can't be parsed, such as one cut short by an interrupted run, are skipped rather than
+// making the whole history unusable. Also returns the errors for the skipped lines.
+pub fn load_history(path: &str) -> Result<(Vec<BenchmarkRun>, Vec<String>), String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok((vec![], vec![])),
+        Err(_) => return Err(format!("Failed to read benchmark history: '{}'", path)),
+    };
+
+    let mut history = Vec::new();
+    let mut skipped = Vec::new();
+    for line in contents.lines() {
+        if line.is_empty() {
+            continue;
+        }
+        match parse_history_line(line) {
+            Ok(run) => history.push(run),
+            Err(error) => skipped.push(error),
+        }
+    }
+    return Ok((history, skipped));
+}
+
+// If the file was left without a final newline, the new run starts on its own line, so that only
+// the cut-short line is lost.
+pub fn append_history(path: &str, run: &BenchmarkRun) -> Result<(), String> {
+    let file = fs::OpenOptions::new().create(true).read(true).append(true).open(path);
+    if file.is_err() {
+        return Err(format!("Failed to open benchmark history: '{}'", path));
+    }
+    let mut file = file.unwrap();
+
+    let mut last_byte = [0u8];
+    let is_unterminated = file.seek(SeekFrom::End(-1)).is_ok() && file.read_exact(&mut last_byte).is_ok() && last_byte[0] != b'\n';
+    let separator = if is_unterminated { "\n" } else { "" };
+    if writeln!(file, "{}{}", separator, format_history_line(run)).is_err() {
+        return Err(format!("Failed to write benchmark history: '{}'", path));
+    }
+    return Ok(());
+}
+
+// The baseline is the run with the median mean among the most recent stored runs of the same day
+// and part on the same input. A single slow run doesn't move it, and a gradual slowdown is measured
+// against runs from before it started.
+pub fn find_baseline<'a>(history: &'a [BenchmarkRun], run: &BenchmarkRun) -> Option<&'a BenchmarkRun> {
+    let mut recent_runs: Vec<&BenchmarkRun> = history
+        .iter()
+        .rev()
+        .filter(|entry| entry.day == run.day && entry.part == run.part && entry.input_hash == run.input_hash)
+        .take(BASELINE_RUNS)
+        .collect();
+    if recent_runs.is_empty() {
+        return None;
+    }
+    recent_runs.sort_by(|a, b| a.mean().total_cmp(&b.mean()));
+    return Some(recent_runs[recent_runs.len() / 2]);
+}
+
+pub fn compare(baseline: &BenchmarkRun, run: &BenchmarkRun) -> Comparison {
+    let baseline_mean = baseline.mean();
+    let mean = run.mean();
+    let change = if baseline_mean > 0.0 {
+        (mean - baseline_mean) / baseline_mean
+    } else {
+        0.0
+    };
+
+    let standard_error = (baseline.variance() / baseline.timings.len() as f64
+        + run.variance() / run.timings.len() as f64)
+        .sqrt();
+    let t_statistic = if standard_error > 0.0 {
+        (mean - baseline_mean) / standard_error
+    } else if mean > baseline_mean {
+        f64::INFINITY
+    } else {
+        0.0
+    };
+
+    return Comparison {
+        baseline_revision: baseline.revision.clone(),
+        change,
+        t_statistic,
+        is_regression: t_statistic > T_STATISTIC_THRESHOLD && change > MINIMUM_SLOWDOWN,
+    };
+}
@@ -0,0 +1,202 @@
+use std::fs;
+use std::sync::Mutex;
+
+use crate::days::{self, Part, SolveOptions};
+
+pub static DEFAULT_CACHE_PATH: &str = ".aoc-answer-cache";
+
+// Held while reading or rewriting the cache file, so parts solved in parallel neither read a cache
+// that is being replaced nor drop each other's answers. It isn't held while solving.
+static CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+// An answer from `solve_with_cache`. Failing to store the answer doesn't fail the solve, so the
+// error is returned alongside it for the caller to report.
+#[derive(Debug, Clone)]
+pub struct CachedAnswer {
+    pub answer: String,
+    pub save_error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub day: u8,
+    pub part: Part,
+    pub input_hash: u64,
+    pub solver_version: u32,
+    pub answer: String,
+}
+
+impl CacheEntry {
+    pub fn is_current(&self) -> bool {
+        return match days::get_solver_version(self.day) {
+            Ok(version) => version == self.solver_version,
+            Err(_) => false,
+        };
+    }
+}
+
+fn escape_answer(answer: &str) -> String {
+    return answer.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n");
+}
+
+fn unescape_answer(raw_answer: &str) -> String {
+    let mut answer = String::new();
+    let mut characters = raw_answer.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            answer.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('t') => answer.push('\t'),
+            Some('n') => answer.push('\n'),
+            Some(other) => answer.push(other),
+            None => answer.push('\\'),
+        }
+    }
+    return answer;
+}
+
+fn format_cache_line(entry: &CacheEntry) -> String {
+    return format!(
+        "{}\t{}\t{:016x}\t{}\t{}",
+        entry.day,
+        entry.part.number(),
+        entry.input_hash,
+        entry.solver_version,
+        escape_answer(&entry.answer)
+    );
+}
+
+fn parse_cache_line(line: &str) -> Result<CacheEntry, String> {
+    let fields: Vec<&str> = line.splitn(5, '\t').collect();
+    if fields.len() != 5 {
+        return Err(format!("Invalid answer cache entry: '{}'", line));
+    }
+
+    let day = fields[0].parse::<u8>();
+    let part = match fields[1] {
+        "1" => Some(Part::Part1),
+        "2" => Some(Part::Part2),
+        _ => None,
+    };
+    let input_hash = u64::from_str_radix(fields[2], 16);
+    let solver_version = fields[3].parse::<u32>();
+
+    if day.is_err() || part.is_none() || input_hash.is_err() || solver_version.is_err() {
+        return Err(format!("Invalid answer cache entry: '{}'", line));
+    }
+
+    return Ok(CacheEntry {
+        day: day.unwrap(),
+        part: part.unwrap(),
+        input_hash: input_hash.unwrap(),
+        solver_version: solver_version.unwrap(),
+        answer: unescape_answer(fields[4]),
+    });
+}
+
+// Lines that can't be parsed, such as those left by an older format, are skipped and so act as
+// cache misses. They are dropped the next time the cache is written.
+pub fn load_cache(path: &str) -> Result<Vec<CacheEntry>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(_) => return Err(format!("Failed to read answer cache: '{}'", path)),
+    };
+
+    let mut entries = Vec::new();
+    for line in contents.lines() {
+        if let Ok(entry) = parse_cache_line(line) {
+            entries.push(entry);
+        }
+    }
+    return Ok(entries);
+}
+
+// Writes to a temporary file next to the cache and renames it into place, so the cache is never
+// left half written.
+fn save_cache(path: &str, entries: &[CacheEntry]) -> Result<(), String> {
+    let mut contents = String::new();
+    for entry in entries {
+        contents.push_str(format_cache_line(entry).as_str());
+        contents.push('\n');
+    }
+    let temporary_path = format!("{}.{}.tmp", path, std::process::id());
+    if fs::write(&temporary_path, contents).is_err() {
+        let _ = fs::remove_file(&temporary_path);
+        return Err(format!("Failed to write answer cache: '{}'", path));
+    }
+    if fs::rename(&temporary_path, path).is_err() {
+        let _ = fs::remove_file(&temporary_path);
+        return Err(format!("Failed to write answer cache: '{}'", path));
+    }
+    return Ok(());
+}
+
+pub fn clear_cache(path: &str) -> Result<usize, String> {
+    let load_result = load_cache(path);
+    let count = match load_result {
+        Ok(entries) => entries.len(),
+        Err(_) => 0,
+    };
+    return match fs::remove_file(path) {
+        Ok(_) => Ok(count),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(_) => Err(format!("Failed to remove answer cache: '{}'", path)),
+    };
+}
+
+fn find_entry(entries: &[CacheEntry], day: u8, part: Part, input_hash: u64) -> Option<&CacheEntry> {
+    return entries
+        .iter()
+        .find(|entry| entry.day == day && entry.part == part && entry.input_hash == input_hash && entry.is_current());
+}
+
+// Returns the cached answer if there is one for this solver version, otherwise solves and stores
+// the answer. Entries left behind by older solver versions are dropped whenever the cache is
+// written. Errors are never cached, and a cache that can't be read or written only costs a solve.
+pub fn solve_with_cache(path: &str, day: u8, part: Part, input: Option<String>, options: &SolveOptions) -> Result<CachedAnswer, String> {
+    let input_result = days::get_input_text(day, input);
+    if input_result.is_err() {
+        return Err(input_result.err().unwrap());
+    }
+    let input_text = input_result.unwrap();
+    let input_hash = days::hash_variant_input(&input_text, options);
+
+    let cached_entries = {
+        let _guard = CACHE_LOCK.lock();
+        load_cache(path)
+    };
+    if let Ok(entries) = cached_entries {
+        if let Some(entry) = find_entry(&entries, day, part, input_hash) {
+            return Ok(CachedAnswer { answer: entry.answer.clone(), save_error: None });
+        }
+    }
+
+    let solve_result = days::solve_with_options(day, part, Some(input_text), options);
+    if solve_result.is_err() {
+        return Err(solve_result.err().unwrap());
+    }
+    let answer = solve_result.unwrap();
+
+    let solver_version = days::get_solver_version(day);
+    if solver_version.is_err() {
+        return Err(solver_version.err().unwrap());
+    }
+    let _guard = CACHE_LOCK.lock();
+    let mut entries = match load_cache(path) {
+        Ok(entries) => entries,
+        Err(error) => return Ok(CachedAnswer { answer, save_error: Some(error) }),
+    };
+    entries.retain(|entry| entry.is_current() && !(entry.day == day && entry.part == part && entry.input_hash == input_hash));
+    entries.push(CacheEntry {
+        day,
+        part,
+        input_hash,
+        solver_version: solver_version.unwrap(),
+        answer: answer.clone(),
+    });
+
+    return Ok(CachedAnswer { answer, save_error: save_cache(path, &entries).err() });
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub static CONFIG_FILE_NAME: &str = "aoc.toml";
+
+pub static CONFIG_KEYS: [&str; 6] = ["input_dir", "part", "format", "color", "jobs", "answer_file"];
+
+// Keys holding paths, which are resolved relative to the config file they were read from.
+static PATH_KEYS: [&str; 2] = ["input_dir", "answer_file"];
+
+#[derive(Debug, Clone)]
+pub struct ConfigValue {
+    pub value: String,
+    pub source: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Config {
+    values: HashMap<String, ConfigValue>,
+}
+
+impl Config {
+    pub fn get(&self, key: &str) -> Option<&ConfigValue> {
+        return self.values.get(key);
+    }
+
+    fn merge(&mut self, other: Config) {
+        for (key, value) in other.values {
+            self.values.insert(key, value);
+        }
+    }
+}
+
+pub fn get_env_var_name(key: &str) -> String {
+    return format!("AOC_{}", key.to_uppercase());
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    return value;
+}
+
+pub fn parse_config(contents: &str, path: &Path) -> Result<Config, String> {
+    let mut config = Config::default();
+    let base_directory = path.parent().unwrap_or(Path::new(""));
+
+    for (index, raw_line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let split_result = line.split_once('=');
+        if split_result.is_none() {
+            return Err(format!("Expected 'key = value' at {}:{}: '{}'", path.display(), line_number, raw_line));
+        }
+        let (raw_key, raw_value) = split_result.unwrap();
+        let key = raw_key.trim();
+        let mut value = unquote(raw_value.trim()).to_string();
+
+        if !CONFIG_KEYS.contains(&key) {
+            return Err(format!("Unrecognized config key at {}:{}: '{}'", path.display(), line_number, key));
+        }
+        if PATH_KEYS.contains(&key) {
+            value = base_directory.join(value).to_string_lossy().to_string();
+        }
+
+        config.values.insert(
+            key.to_string(),
+            ConfigValue {
+                value,
+                source: format!("{}:{}", path.display(), line_number),
+            },
+        );
+    }
+
+    return Ok(config);
+}
+
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    let mut directory = Some(start);
+    while let Some(current) = directory {
+        let candidate = current.join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        directory = current.parent();
+    }
+    return None;
+}
+
+pub fn get_user_config_path() -> Option<PathBuf> {
+    let config_directory = match env::var_os("XDG_CONFIG_HOME") {
+        Some(directory) if !directory.is_empty() => PathBuf::from(directory),
+        _ => match env::var_os("HOME") {
+            Some(home) if !home.is_empty() => PathBuf::from(home).join(".config"),
+            _ => return None,
+        },
+    };
+    return Some(config_directory.join("aoc").join(CONFIG_FILE_NAME));
+}
+
+fn load_config_file(path: &Path) -> Result<Config, String> {
+    return match fs::read_to_string(path) {
+        Ok(contents) => parse_config(&contents, path),
+        Err(_) => Err(format!("Failed to read config: '{}'", path.display())),
+    };
+}
+
+// Loads the user config, then the nearest project config (searching upwards from the working
+// directory), with project values taking precedence.
+pub fn load_config() -> Result<Config, String> {
+    let mut config = Config::default();
+
+    if let Some(user_config_path) = get_user_config_path() {
+        if user_config_path.is_file() {
+            match load_config_file(&user_config_path) {
+                Ok(user_config) => config.merge(user_config),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    if let Ok(working_directory) = env::current_dir() {
+        if let Some(project_config_path) = find_project_config(&working_directory) {
+            match load_config_file(&project_config_path) {
+                Ok(project_config) => config.merge(project_config),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    return Ok(config);
+}
@@ -6,3 +6,162 @@ pub mod day5;
 pub mod day6;
 pub mod day7;
 pub mod day8;
+
+pub static IMPLEMENTED_DAYS: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    Part1,
+    Part2,
+}
+
+impl Part {
+    pub fn number(&self) -> u8 {
+        return match self {
+            Part::Part1 => 1,
+            Part::Part2 => 2,
+        };
+    }
+}
+
+// Settings for solvers that support variants of the puzzle. The defaults solve the puzzle as written.
+#[derive(Debug, Clone, Default)]
+pub struct SolveOptions {
+    pub rules: Option<day2::Ruleset>,
+    pub compartments: Option<usize>,
+    pub group_size: Option<usize>,
+    pub reversed_ranges: Option<day4::ReversedRangePolicy>,
+    // Replaces the crane of both parts.
+    pub crane: Option<day5::Crane>,
+}
+
+impl SolveOptions {
+    pub fn get_variant_key(&self) -> String {
+        let mut keys = Vec::new();
+        if let Some(rules) = &self.rules {
+            keys.push(format!("rules:{}", rules.to_definition()));
+        }
+        if let Some(compartments) = self.compartments {
+            keys.push(format!("compartments:{}", compartments));
+        }
+        if let Some(group_size) = self.group_size {
+            keys.push(format!("group_size:{}", group_size));
+        }
+        if let Some(policy) = self.reversed_ranges {
+            keys.push(format!("reversed_ranges:{:?}", policy));
+        }
+        if let Some(crane) = self.crane {
+            keys.push(format!("crane:{}", crane.name()));
+        }
+        return keys.join("\n");
+    }
+}
+
+pub fn get_example_input(day: u8) -> Result<&'static str, String> {
+    return match day {
+        1 => Ok(day1::EXAMPLE_INPUT),
+        2 => Ok(day2::EXAMPLE_INPUT),
+        3 => Ok(day3::EXAMPLE_INPUT),
+        4 => Ok(day4::EXAMPLE_INPUT),
+        5 => Ok(day5::EXAMPLE_INPUT),
+        6 => Ok(day6::EXAMPLE_INPUT),
+        7 => Ok(day7::EXAMPLE_INPUT),
+        8 => Ok(day8::EXAMPLE_INPUT),
+        _ => Err(format!("Day not found: '{}'", day)),
+    };
+}
+
+pub fn get_input_text(day: u8, custom_input: Option<String>) -> Result<String, String> {
+    return match custom_input {
+        Some(custom_input) => Ok(custom_input),
+        None => match get_example_input(day) {
+            Ok(example_input) => Ok(example_input.to_string()),
+            Err(error) => Err(error),
+        },
+    };
+}
+
+fn continue_hash(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    return hash;
+}
+
+// FNV-1a, used so that hashes stay stable between builds and Rust versions.
+pub fn hash_input(input: &str) -> u64 {
+    return continue_hash(0xcbf29ce484222325, input.as_bytes());
+}
+
+// Variants hash their key ahead of the input, so their answers are cached separately.
+pub fn hash_variant_input(input: &str, options: &SolveOptions) -> u64 {
+    let variant_key = options.get_variant_key();
+    if variant_key.is_empty() {
+        return hash_input(input);
+    }
+    let hash = continue_hash(0xcbf29ce484222325, variant_key.as_bytes());
+    return continue_hash(continue_hash(hash, &[0]), input.as_bytes());
+}
+
+// Cached answers are only reused while the day's version matches the one they were stored with.
+// Each day's SOLVER_VERSION is maintained by hand, and must be bumped whenever a change to that
+// solver, its parser or its variants could change an answer for the same input.
+pub fn get_solver_version(day: u8) -> Result<u32, String> {
+    return match day {
+        1 => Ok(day1::SOLVER_VERSION),
+        2 => Ok(day2::SOLVER_VERSION),
+        3 => Ok(day3::SOLVER_VERSION),
+        4 => Ok(day4::SOLVER_VERSION),
+        5 => Ok(day5::SOLVER_VERSION),
+        6 => Ok(day6::SOLVER_VERSION),
+        7 => Ok(day7::SOLVER_VERSION),
+        8 => Ok(day8::SOLVER_VERSION),
+        _ => Err(format!("Day not found: '{}'", day)),
+    };
+}
+
+pub fn solve(day: u8, part: Part, input: Option<String>) -> Result<String, String> {
+    return solve_with_options(day, part, input, &SolveOptions::default());
+}
+
+pub fn solve_with_options(day: u8, part: Part, input: Option<String>, options: &SolveOptions) -> Result<String, String> {
+    return match day {
+        1 => match part {
+            Part::Part1 => day1::solve_part_1(input),
+            Part::Part2 => day1::solve_part_2(input),
+        },
+        2 => match (part, &options.rules) {
+            (Part::Part1, Some(rules)) => day2::solve_part_1_with_rules(input, rules),
+            (Part::Part2, Some(rules)) => day2::solve_part_2_with_rules(input, rules),
+            (Part::Part1, None) => day2::solve_part_1(input),
+            (Part::Part2, None) => day2::solve_part_2(input),
+        },
+        3 => match part {
+            Part::Part1 => day3::solve_part_1_with_compartments(input, options.compartments.unwrap_or(day3::DEFAULT_COMPARTMENTS)),
+            Part::Part2 => day3::solve_part_2_with_group_size(input, options.group_size.unwrap_or(day3::DEFAULT_GROUP_SIZE)),
+        },
+        4 => match part {
+            Part::Part1 => day4::solve_part_1_with_policy(input, options.reversed_ranges.unwrap_or_default()),
+            Part::Part2 => day4::solve_part_2_with_policy(input, options.reversed_ranges.unwrap_or_default()),
+        },
+        5 => match (part, options.crane) {
+            (_, Some(crane)) => day5::solve_with_crane(input, crane),
+            (Part::Part1, None) => day5::solve_part_1(input),
+            (Part::Part2, None) => day5::solve_part_2(input),
+        },
+        6 => match part {
+            Part::Part1 => day6::solve_part_1(input),
+            Part::Part2 => day6::solve_part_2(input),
+        },
+        7 => match part {
+            Part::Part1 => day7::solve_part_1(input),
+            Part::Part2 => day7::solve_part_2(input),
+        },
+        8 => match part {
+            Part::Part1 => day8::solve_part_1(input),
+            Part::Part2 => day8::solve_part_2(input),
+        },
+        _ => Err(format!("Day not found: '{}'", day)),
+    };
+}
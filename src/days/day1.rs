@@ -1,6 +1,15 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+
+use crate::days::Part;
+use crate::report::{Alignment, Table};
+use crate::stats;
 
 pub static EXAMPLE_INPUT: &str = include_str!("../example_input/day1.txt");
 
+pub static SOLVER_VERSION: u32 = 2;
+
 fn get_input(custom_input: Option<String>) -> String {
     return match custom_input {
         Some(custom_input) => custom_input,
@@ -8,60 +17,384 @@ fn get_input(custom_input: Option<String>) -> String {
     };
 }
 
-fn get_calorie_groups(input: String) -> Result<Vec<u64>, String> {
-    let mut groups = Vec::new();
-    let mut current_calories: u64 = 0;
-    for line in input.lines() {
-        match line {
-            "" => {
-                groups.push(current_calories);
-                current_calories = 0;
-            }
-            calories if calories.parse::<u64>().is_ok() => {
-                current_calories += calories.parse::<u64>().unwrap();
-            }
-            _ => {
-                return Err(format!("Invalid input: '{}'", line));
+#[derive(Debug)]
+struct CalorieGroup {
+    total: u64,
+    items: Vec<u64>,
+}
+
+// Reads elves' calorie groups from a stream of lines, one group at a time. Groups are separated
+// by blank lines, and the last group is included even if the input has no trailing blank line.
+// Items are only kept if requested, so that totals can be read from large inputs without
+// allocating per group.
+struct CalorieGroupReader<I> {
+    lines: I,
+    keep_items: bool,
+    elves_read: usize,
+    finished: bool,
+}
+
+impl<I> CalorieGroupReader<I> {
+    fn new(lines: I, keep_items: bool) -> CalorieGroupReader<I> {
+        return CalorieGroupReader { lines, keep_items, elves_read: 0, finished: false };
+    }
+}
+
+impl<I, L> Iterator for CalorieGroupReader<I>
+where
+    I: Iterator<Item = Result<L, String>>,
+    L: AsRef<str>,
+{
+    type Item = Result<CalorieGroup, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut group = CalorieGroup { total: 0, items: Vec::new() };
+        let mut has_items = false;
+        loop {
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(error)) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+                None => {
+                    self.finished = true;
+                    if !has_items {
+                        return None;
+                    }
+                    self.elves_read += 1;
+                    return Some(Ok(group));
+                }
+            };
+
+            match line.as_ref() {
+                "" => {
+                    self.elves_read += 1;
+                    return Some(Ok(group));
+                }
+                calories if calories.parse::<u64>().is_ok() => {
+                    let item = calories.parse::<u64>().unwrap();
+                    match group.total.checked_add(item) {
+                        Some(total) => group.total = total,
+                        None => {
+                            self.finished = true;
+                            return Some(Err(format!("Calorie total too large for elf {}", self.elves_read + 1)));
+                        }
+                    }
+                    if self.keep_items {
+                        group.items.push(item);
+                    }
+                    has_items = true;
+                }
+                _ => {
+                    self.finished = true;
+                    return Some(Err(format!("Invalid input: '{}'", line.as_ref())));
+                }
             }
         }
     }
+}
 
-    return Ok(groups);
+fn get_string_lines(input: &str) -> impl Iterator<Item = Result<&str, String>> {
+    return input.lines().map(Ok);
 }
 
-pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
+// Keeps the largest `count` totals in a min-heap, so memory stays bounded by `count` no matter
+// how many elves there are.
+fn get_top_calories_sum<I>(groups: I, count: usize) -> Result<u64, String>
+where
+    I: Iterator<Item = Result<CalorieGroup, String>>,
+{
+    if count == 0 {
+        return Err("Must select at least one elf".to_string());
+    }
+
+    let mut largest = BinaryHeap::new();
+    let mut length: usize = 0;
+    for group_result in groups {
+        let group = match group_result {
+            Ok(group) => group,
+            Err(error) => return Err(error),
+        };
+        length += 1;
+        largest.push(Reverse(group.total));
+        if largest.len() > count {
+            largest.pop();
+        }
+    }
+
+    if length < count {
+        return Err(format!("Not enough elves for top {}, only saw '{}'", count, length));
+    }
+
+    let sum = largest.iter().try_fold(0u64, |total, Reverse(calories)| total.checked_add(*calories));
+
+    return match sum {
+        Some(result) => Ok(result),
+        None => Err("Calorie total too large".to_string()),
+    }
+}
+
+pub fn solve_top(custom_input: Option<String>, count: usize) -> Result<String, String> {
     let input = get_input(custom_input);
-    let elves: Vec<u64>;
 
-    match get_calorie_groups(input) {
-        Ok(result) => elves = result,
-        Err(error) => return Err(error),
+    let mut groups = Vec::new();
+    for group_result in CalorieGroupReader::new(get_string_lines(&input), false) {
+        match group_result {
+            Ok(group) => groups.push(group),
+            Err(error) => return Err(error),
+        }
+    }
+    stats::mark_parsed();
+
+    return match get_top_calories_sum(groups.into_iter().map(Ok), count) {
+        Ok(result) => Ok(format!("{}", result)),
+        Err(error) => Err(error),
     }
+}
 
-    let max_calories = elves.iter().max();
-    return match max_calories {
-        Some(result) => Ok(format!("{}", result)),
-        None => Err(format!("No elves found"))
+// Same as `solve_top`, reading the input line by line so it never has to fit in memory. Parsing
+// and solving happen together, so allocations are reported as one phase.
+pub fn solve_top_from_reader<R: BufRead>(reader: R, count: usize) -> Result<String, String> {
+    let lines = reader
+        .lines()
+        .map(|line| line.map_err(|error| format!("Failed to read input: {}", error)));
+    let groups = CalorieGroupReader::new(lines, false);
+    return match get_top_calories_sum(groups, count) {
+        Ok(result) => Ok(format!("{}", result)),
+        Err(error) => Err(error),
     }
 }
 
+fn get_part_count(part: Part) -> usize {
+    return match part {
+        Part::Part1 => 1,
+        Part::Part2 => 3,
+    };
+}
+
+pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
+    return solve_top(custom_input, get_part_count(Part::Part1));
+}
+
 pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
+    return solve_top(custom_input, get_part_count(Part::Part2));
+}
+
+// Solves either part in one pass over the reader, keeping at most three totals in memory.
+pub fn solve_part_from_reader<R: BufRead>(reader: R, part: Part) -> Result<String, String> {
+    return solve_top_from_reader(reader, get_part_count(part));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportOrder {
+    Total,
+    Index,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfReportEntry {
+    pub elf: usize,
+    pub items: Vec<u64>,
+    pub total: u64,
+    pub rank: usize,
+}
+
+// Elves are numbered from 1 in input order. Ranks start at 1 for the largest total, and elves
+// with equal totals share a rank.
+pub fn get_inventory_report(custom_input: Option<String>, order: ReportOrder) -> Result<Vec<ElfReportEntry>, String> {
     let input = get_input(custom_input);
-    let mut elves: Vec<u64>;
 
-    match get_calorie_groups(input) {
-        Ok(result) => elves = result,
+    let mut entries = Vec::new();
+    for (index, group_result) in CalorieGroupReader::new(get_string_lines(&input), true).enumerate() {
+        match group_result {
+            Ok(group) => entries.push(ElfReportEntry {
+                elf: index + 1,
+                items: group.items,
+                total: group.total,
+                rank: 0,
+            }),
+            Err(error) => return Err(error),
+        }
+    }
+
+    entries.sort_by(|a, b| b.total.cmp(&a.total).then(a.elf.cmp(&b.elf)));
+    for position in 0..entries.len() {
+        entries[position].rank = if position > 0 && entries[position].total == entries[position - 1].total {
+            entries[position - 1].rank
+        } else {
+            position + 1
+        };
+    }
+
+    if order == ReportOrder::Index {
+        entries.sort_by_key(|entry| entry.elf);
+    }
+
+    return Ok(entries);
+}
+
+pub fn get_inventory_report_table(custom_input: Option<String>, order: ReportOrder) -> Result<Table, String> {
+    let entries = match get_inventory_report(custom_input, order) {
+        Ok(result) => result,
         Err(error) => return Err(error),
+    };
+
+    let mut table = Table::new(&[
+        ("Elf", Alignment::Right),
+        ("Items", Alignment::Right),
+        ("Calories", Alignment::Left),
+        ("Total", Alignment::Right),
+        ("Rank", Alignment::Right),
+    ]);
+    for entry in entries {
+        let items: Vec<String> = entry.items.iter().map(|calories| calories.to_string()).collect();
+        table.add_row(vec![
+            entry.elf.to_string(),
+            entry.items.len().to_string(),
+            items.join(" "),
+            entry.total.to_string(),
+            entry.rank.to_string(),
+        ]);
     }
 
-    let length = elves.len();
+    return Ok(table);
+}
 
-    if length < 3 {
-        return Err(format!("Not enough elves, only saw '{}'", length))
+static HISTOGRAM_BINS: u64 = 10;
+static HISTOGRAM_WIDTH: u64 = 40;
+
+pub static DEFAULT_PERCENTILES: [f64; 4] = [25.0, 50.0, 75.0, 90.0];
+
+#[derive(Debug, Clone)]
+pub struct CalorieStats {
+    pub elves: usize,
+    pub total: u64,
+    pub mean: f64,
+    pub median: f64,
+    pub min: u64,
+    pub max: u64,
+    pub standard_deviation: f64,
+    pub percentiles: Vec<(f64, f64)>,
+    pub histogram: Vec<(u64, u64, usize)>,
+}
+
+// Linear interpolation between the closest ranks, on totals sorted in ascending order.
+fn get_percentile(sorted_totals: &[u64], percentile: f64) -> f64 {
+    let position = (percentile / 100.0) * (sorted_totals.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f64;
+    return sorted_totals[lower] as f64 + (sorted_totals[upper] as f64 - sorted_totals[lower] as f64) * fraction;
+}
+
+fn get_histogram(sorted_totals: &[u64], min: u64, max: u64) -> Vec<(u64, u64, usize)> {
+    let span = (max - min).saturating_add(1);
+    let bin_width = span.div_ceil(HISTOGRAM_BINS).max(1);
+    let mut histogram = Vec::new();
+    let mut bin_start = min;
+    loop {
+        let bin_end = bin_start.saturating_add(bin_width - 1).min(max);
+        let count = sorted_totals
+            .iter()
+            .filter(|total| **total >= bin_start && **total <= bin_end)
+            .count();
+        histogram.push((bin_start, bin_end, count));
+        if bin_end >= max {
+            break;
+        }
+        bin_start = bin_end + 1;
+    }
+    return histogram;
+}
+
+pub fn get_calorie_stats(custom_input: Option<String>, percentiles: &[f64]) -> Result<CalorieStats, String> {
+    for percentile in percentiles {
+        if !(0.0..=100.0).contains(percentile) {
+            return Err(format!("Percentile out of range: '{}'", percentile));
+        }
+    }
+
+    let input = get_input(custom_input);
+    let mut totals = Vec::new();
+    for group_result in CalorieGroupReader::new(get_string_lines(&input), false) {
+        match group_result {
+            Ok(group) => totals.push(group.total),
+            Err(error) => return Err(error),
+        }
+    }
+    if totals.is_empty() {
+        return Err("No elves found".to_string());
     }
+    totals.sort();
 
-    elves.sort_by(|a, b| b.cmp(a));
-    let sum: u64 = elves.iter().take(3).sum();
+    let total = match totals.iter().try_fold(0u64, |sum, calories| sum.checked_add(*calories)) {
+        Some(total) => total,
+        None => return Err("Calorie total too large".to_string()),
+    };
+    let elves = totals.len();
+    let mean = total as f64 / elves as f64;
+    // population standard deviation, since every elf is counted
+    let variance = totals
+        .iter()
+        .map(|calories| (*calories as f64 - mean).powi(2))
+        .sum::<f64>()
+        / elves as f64;
+    let min = totals[0];
+    let max = totals[elves - 1];
+
+    return Ok(CalorieStats {
+        elves,
+        total,
+        mean,
+        median: get_percentile(&totals, 50.0),
+        min,
+        max,
+        standard_deviation: variance.sqrt(),
+        percentiles: percentiles
+            .iter()
+            .map(|percentile| (*percentile, get_percentile(&totals, *percentile)))
+            .collect(),
+        histogram: get_histogram(&totals, min, max),
+    });
+}
+
+pub fn get_calorie_stats_tables(custom_input: Option<String>, percentiles: &[f64]) -> Result<Vec<Table>, String> {
+    let stats = match get_calorie_stats(custom_input, percentiles) {
+        Ok(result) => result,
+        Err(error) => return Err(error),
+    };
+
+    let mut summary = Table::new(&[("Statistic", Alignment::Left), ("Value", Alignment::Right)]);
+    summary.add_row(vec!["Elves".to_string(), stats.elves.to_string()]);
+    summary.add_row(vec!["Total".to_string(), stats.total.to_string()]);
+    summary.add_row(vec!["Mean".to_string(), format!("{:.2}", stats.mean)]);
+    summary.add_row(vec!["Median".to_string(), format!("{:.2}", stats.median)]);
+    summary.add_row(vec!["Min".to_string(), stats.min.to_string()]);
+    summary.add_row(vec!["Max".to_string(), stats.max.to_string()]);
+    summary.add_row(vec!["Standard deviation".to_string(), format!("{:.2}", stats.standard_deviation)]);
+    for (percentile, value) in &stats.percentiles {
+        summary.add_row(vec![format!("P{}", percentile), format!("{:.2}", value)]);
+    }
+
+    let largest_bin = stats.histogram.iter().map(|(_, _, count)| *count).max().unwrap_or(0).max(1) as u64;
+    let mut histogram = Table::new(&[
+        ("Calories", Alignment::Left),
+        ("Elves", Alignment::Right),
+        ("Distribution", Alignment::Left),
+    ]);
+    for (bin_start, bin_end, count) in &stats.histogram {
+        let bar_length = (*count as u64 * HISTOGRAM_WIDTH).div_ceil(largest_bin);
+        histogram.add_row(vec![
+            format!("{}-{}", bin_start, bin_end),
+            count.to_string(),
+            "#".repeat(bar_length as usize),
+        ]);
+    }
 
-    return Ok(format!("{}", sum));
+    return Ok(vec![summary, histogram]);
 }
@@ -1,6 +1,45 @@
+use std::collections::HashSet;
+
+use crate::days::Part;
+use crate::report::{Alignment, Table};
+use crate::stats;
 
 pub static EXAMPLE_INPUT: &str = include_str!("../example_input/day2.txt");
 
+pub static SOLVER_VERSION: u32 = 1;
+
+pub static RPS_RULES: &str = "\
+name = rps
+shape = Rock A X 1
+shape = Paper B Y 2
+shape = Scissors C Z 3
+beats = Rock Scissors
+beats = Paper Rock
+beats = Scissors Paper
+lose = X 0
+draw = Y 3
+win = Z 6
+";
+
+pub static RPSLS_RULES: &str = "\
+name = rpsls
+shape = Rock A V 1
+shape = Paper B W 2
+shape = Scissors C X 3
+shape = Lizard D Y 4
+shape = Spock E Z 5
+beats = Rock Scissors Lizard
+beats = Paper Rock Spock
+beats = Scissors Paper Lizard
+beats = Lizard Paper Spock
+beats = Spock Rock Scissors
+lose = X 0
+draw = Y 3
+win = Z 6
+";
+
+pub static BUILTIN_RULESETS: [(&str, &str); 2] = [("rps", RPS_RULES), ("rpsls", RPSLS_RULES)];
+
 fn get_input(custom_input: Option<String>) -> String {
     return match custom_input {
         Some(custom_input) => custom_input,
@@ -9,74 +48,281 @@ fn get_input(custom_input: Option<String>) -> String {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-enum Choice {
-    Rock,
-    Paper,
-    Scissors,
-}
-
 enum Winner {
     Opponent,
     Tied,
     You,
 }
 
-fn parse_opponent_choice(choice: &str) -> Result<Choice, String> {
-    return match choice {
-        "A" => Ok(Choice::Rock),
-        "B" => Ok(Choice::Paper),
-        "C" => Ok(Choice::Scissors),
-        _ => Err(format!("Invalid opponent choice: '{}'", choice))
-    }
+static OUTCOMES: [Winner; 3] = [Winner::Opponent, Winner::Tied, Winner::You];
+
+#[derive(Debug, Clone)]
+pub struct Shape {
+    pub name: String,
+    pub opponent_symbol: String,
+    pub your_symbol: String,
+    pub score: i64,
+}
+
+// A cyclic dominance game: an odd number of shapes, where each shape beats exactly half of the
+// others. Outcome symbols and scores are listed as lose, draw, win.
+#[derive(Debug, Clone)]
+pub struct Ruleset {
+    pub name: String,
+    pub shapes: Vec<Shape>,
+    beats: Vec<Vec<bool>>,
+    pub outcome_symbols: [String; 3],
+    pub outcome_scores: [i64; 3],
 }
 
-fn parse_your_choice_incorrectly(choice: &str) -> Result<Choice, String> {
-    return match choice {
-        "X" => Ok(Choice::Rock),
-        "Y" => Ok(Choice::Paper),
-        "Z" => Ok(Choice::Scissors),
-        _ => Err(format!("Invalid choice: '{}'", choice))
+impl Ruleset {
+    pub fn beats(&self, shape: usize, other: usize) -> bool {
+        return self.beats[shape][other];
+    }
+
+    // Writes the ruleset back out in the definition format, with every shape's wins on one line.
+    pub fn to_definition(&self) -> String {
+        let mut lines = vec![format!("name = {}", self.name)];
+        for shape in &self.shapes {
+            lines.push(format!("shape = {} {} {} {}", shape.name, shape.opponent_symbol, shape.your_symbol, shape.score));
+        }
+        for (index, shape) in self.shapes.iter().enumerate() {
+            let losers: Vec<&str> = (0..self.shapes.len())
+                .filter(|other| self.beats(index, *other))
+                .map(|other| self.shapes[other].name.as_str())
+                .collect();
+            lines.push(format!("beats = {} {}", shape.name, losers.join(" ")));
+        }
+        for (index, key) in ["lose", "draw", "win"].iter().enumerate() {
+            lines.push(format!("{} = {} {}", key, self.outcome_symbols[index], self.outcome_scores[index]));
+        }
+        return lines.join("\n") + "\n";
+    }
+
+    fn find_shape(&self, name: &str) -> Option<usize> {
+        return self.shapes.iter().position(|shape| shape.name == name);
+    }
+
+    fn parse_opponent_choice(&self, choice: &str) -> Result<usize, String> {
+        return match self.shapes.iter().position(|shape| shape.opponent_symbol == choice) {
+            Some(index) => Ok(index),
+            None => Err(format!("Invalid opponent choice: '{}'", choice)),
+        }
+    }
+
+    fn parse_your_choice_incorrectly(&self, choice: &str) -> Result<usize, String> {
+        return match self.shapes.iter().position(|shape| shape.your_symbol == choice) {
+            Some(index) => Ok(index),
+            None => Err(format!("Invalid choice: '{}'", choice)),
+        }
+    }
+
+    fn parse_outcome(&self, choice: &str) -> Result<Winner, String> {
+        return match self.outcome_symbols.iter().position(|symbol| symbol == choice) {
+            Some(index) => Ok(OUTCOMES[index].clone()),
+            None => Err(format!("Invalid outcome: '{}'", choice)),
+        }
+    }
+
+    fn get_winner(&self, opponent_choice: usize, your_choice: usize) -> Winner {
+        if opponent_choice == your_choice {
+            return Winner::Tied;
+        } else if self.beats(opponent_choice, your_choice) {
+            return Winner::Opponent;
+        }
+        return Winner::You;
+    }
+
+    fn get_outcome_score(&self, outcome: &Winner) -> i64 {
+        return match outcome {
+            Winner::Opponent => self.outcome_scores[0],
+            Winner::Tied => self.outcome_scores[1],
+            Winner::You => self.outcome_scores[2],
+        }
+    }
+
+    fn get_round_score(&self, opponent_choice: usize, your_choice: usize) -> i64 {
+        let outcome = self.get_winner(opponent_choice, your_choice);
+        return self.shapes[your_choice].score + self.get_outcome_score(&outcome);
+    }
+
+    // With more than three shapes several choices give the same outcome, so the highest scoring
+    // one is played, taking the first declared on a tie.
+    fn get_your_choice(&self, opponent_choice: usize, outcome: &Winner) -> usize {
+        let mut best: Option<usize> = None;
+        for choice in 0..self.shapes.len() {
+            if self.get_winner(opponent_choice, choice) != *outcome {
+                continue;
+            }
+            if best.is_none() || self.shapes[choice].score > self.shapes[best.unwrap()].score {
+                best = Some(choice);
+            }
+        }
+        // every shape ties with itself and beats and loses to at least one other, so one is found
+        return best.unwrap();
     }
 }
 
-fn get_choice_score(choice: &Choice) -> i32 {
-    return match choice {
-        Choice::Rock => 1,
-        Choice::Paper => 2,
-        Choice::Scissors => 3,
+fn parse_score(score: &str, line_number: usize) -> Result<i64, String> {
+    return match score.parse::<i32>() {
+        Ok(score) => Ok(score as i64),
+        Err(_) => Err(format!("Invalid score at line {}: '{}'", line_number, score)),
     }
 }
 
-fn get_winner(opponent_choice: &Choice, your_choice: &Choice) -> Winner {
-    return match (opponent_choice, your_choice) {
-        (Choice::Rock, Choice::Rock) => Winner::Tied,
-        (Choice::Paper, Choice::Paper) => Winner::Tied,
-        (Choice::Scissors, Choice::Scissors) => Winner::Tied,
-        (Choice::Rock, Choice::Scissors) => Winner::Opponent,
-        (Choice::Scissors, Choice::Paper) => Winner::Opponent,
-        (Choice::Paper, Choice::Rock) => Winner::Opponent,
-        (Choice::Scissors, Choice::Rock) => Winner::You,
-        (Choice::Paper, Choice::Scissors) => Winner::You,
-        (Choice::Rock, Choice::Paper) => Winner::You,
+pub fn parse_ruleset(definition: &str) -> Result<Ruleset, String> {
+    let mut name = String::from("custom");
+    let mut shapes: Vec<Shape> = Vec::new();
+    let mut wins: Vec<(usize, String, Vec<String>)> = Vec::new();
+    let mut outcomes: [Option<(String, i64)>; 3] = [None, None, None];
+
+    for (index, raw_line) in definition.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let split_result = line.split_once('=');
+        if split_result.is_none() {
+            return Err(format!("Expected 'key = value' at line {}: '{}'", line_number, raw_line));
+        }
+        let (raw_key, raw_value) = split_result.unwrap();
+        let key = raw_key.trim();
+        let fields: Vec<&str> = raw_value.split_whitespace().collect();
+
+        match (key, fields.as_slice()) {
+            ("name", [value]) => name = value.to_string(),
+            ("shape", [shape_name, opponent_symbol, your_symbol, score]) => {
+                let score = match parse_score(score, line_number) {
+                    Ok(score) => score,
+                    Err(error) => return Err(error),
+                };
+                shapes.push(Shape {
+                    name: shape_name.to_string(),
+                    opponent_symbol: opponent_symbol.to_string(),
+                    your_symbol: your_symbol.to_string(),
+                    score,
+                });
+            }
+            ("beats", [winner, losers @ ..]) if !losers.is_empty() => {
+                wins.push((line_number, winner.to_string(), losers.iter().map(|loser| loser.to_string()).collect()));
+            }
+            ("lose" | "draw" | "win", [symbol, score]) => {
+                let score = match parse_score(score, line_number) {
+                    Ok(score) => score,
+                    Err(error) => return Err(error),
+                };
+                let position = match key {
+                    "lose" => 0,
+                    "draw" => 1,
+                    _ => 2,
+                };
+                outcomes[position] = Some((symbol.to_string(), score));
+            }
+            ("name" | "shape" | "beats" | "lose" | "draw" | "win", _) => {
+                return Err(format!("Invalid value for '{}' at line {}: '{}'", key, line_number, raw_value.trim()));
+            }
+            _ => return Err(format!("Unrecognized rules key at line {}: '{}'", line_number, key)),
+        }
+    }
+
+    if shapes.len() < 3 || shapes.len().is_multiple_of(2) {
+        return Err(format!("Ruleset must have an odd number of at least 3 shapes, found {}", shapes.len()));
+    }
+    for (field, values) in [
+        ("shape name", shapes.iter().map(|shape| shape.name.as_str()).collect::<Vec<&str>>()),
+        ("opponent symbol", shapes.iter().map(|shape| shape.opponent_symbol.as_str()).collect()),
+        ("your symbol", shapes.iter().map(|shape| shape.your_symbol.as_str()).collect()),
+        ("outcome symbol", outcomes.iter().flatten().map(|(symbol, _)| symbol.as_str()).collect()),
+    ] {
+        let mut seen = HashSet::new();
+        for value in values {
+            if !seen.insert(value) {
+                return Err(format!("Duplicate {}: '{}'", field, value));
+            }
+        }
+    }
+
+    let mut ruleset = Ruleset {
+        name,
+        beats: vec![vec![false; shapes.len()]; shapes.len()],
+        shapes,
+        outcome_symbols: [String::new(), String::new(), String::new()],
+        outcome_scores: [0, 0, 0],
+    };
+    for (index, key) in ["lose", "draw", "win"].iter().enumerate() {
+        match &outcomes[index] {
+            Some((symbol, score)) => {
+                ruleset.outcome_symbols[index] = symbol.clone();
+                ruleset.outcome_scores[index] = *score;
+            }
+            None => return Err(format!("Missing outcome: '{}'", key)),
+        }
+    }
+
+    for (line_number, winner, losers) in wins {
+        let winner_index = match ruleset.find_shape(&winner) {
+            Some(index) => index,
+            None => return Err(format!("Unknown shape at line {}: '{}'", line_number, winner)),
+        };
+        for loser in losers {
+            let loser_index = match ruleset.find_shape(&loser) {
+                Some(index) => index,
+                None => return Err(format!("Unknown shape at line {}: '{}'", line_number, loser)),
+            };
+            if winner_index == loser_index {
+                return Err(format!("Shape can't beat itself at line {}: '{}'", line_number, winner));
+            }
+            ruleset.beats[winner_index][loser_index] = true;
+        }
+    }
+
+    let shape_count = ruleset.shapes.len();
+    for shape in 0..shape_count {
+        for other in (shape + 1)..shape_count {
+            let (name, other_name) = (&ruleset.shapes[shape].name, &ruleset.shapes[other].name);
+            match (ruleset.beats(shape, other), ruleset.beats(other, shape)) {
+                (true, true) => return Err(format!("Shapes beat each other: '{}' and '{}'", name, other_name)),
+                (false, false) => return Err(format!("Neither shape beats the other: '{}' and '{}'", name, other_name)),
+                _ => (),
+            }
+        }
+        let wins = (0..shape_count).filter(|other| ruleset.beats(shape, *other)).count();
+        if wins != shape_count / 2 {
+            return Err(format!(
+                "Shape must beat exactly {} others, '{}' beats {}",
+                shape_count / 2,
+                ruleset.shapes[shape].name,
+                wins
+            ));
+        }
     }
+
+    return Ok(ruleset);
 }
 
-fn get_outcome_score(outcome: &Winner) -> i32 {
-    return match outcome {
-        Winner::Opponent => 0,
-        Winner::Tied => 3,
-        Winner::You => 6,
+pub fn get_builtin_ruleset(name: &str) -> Result<Ruleset, String> {
+    for (builtin_name, definition) in BUILTIN_RULESETS {
+        if builtin_name == name {
+            return parse_ruleset(definition);
+        }
     }
+    return Err(format!("Ruleset not found: '{}'", name));
 }
 
-fn parse_line_incorrectly(line: &str) -> Result<(Choice, Choice), String> {
-    let opponents_choice: Choice;
-    let your_choice: Choice;
+pub fn get_default_ruleset() -> Ruleset {
+    return parse_ruleset(RPS_RULES).unwrap();
+}
+
+fn parse_line_incorrectly(line: &str, rules: &Ruleset) -> Result<(usize, usize), String> {
+    let opponents_choice: usize;
+    let your_choice: usize;
 
     match line.split_once(" ") {
         Some((theirs, yours)) => {
-            let parsed_opponent_choice = parse_opponent_choice(theirs);
-            let parsed_your_choice = parse_your_choice_incorrectly(yours);
+            let parsed_opponent_choice = rules.parse_opponent_choice(theirs);
+            let parsed_your_choice = rules.parse_your_choice_incorrectly(yours);
             match (parsed_opponent_choice, parsed_your_choice) {
                 (Ok(valid_opponent_choice), Ok(valid_your_choice)) => {
                     opponents_choice = valid_opponent_choice;
@@ -92,49 +338,38 @@ fn parse_line_incorrectly(line: &str) -> Result<(Choice, Choice), String> {
     return Ok((opponents_choice, your_choice));
 }
 
-pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
+pub fn solve_part_1_with_rules(custom_input: Option<String>, rules: &Ruleset) -> Result<String, String> {
     let input = get_input(custom_input);
 
-    let mut score = 0;
+    let mut rounds = Vec::new();
     for line in input.lines() {
-        let opponent_choice: Choice;
-        let your_choice: Choice;
-
-        match parse_line_incorrectly(line) {
-            Ok((them, you)) => {
-                opponent_choice = them;
-                your_choice = you;
-            }
+        match parse_line_incorrectly(line, rules) {
+            Ok(round) => rounds.push(round),
             Err(error) => return Err(error)
         }
+    }
+    stats::mark_parsed();
 
-        let choice_score = get_choice_score(&your_choice);
-        let winner = get_winner(&opponent_choice, &your_choice);
-        let outcome_score = get_outcome_score(&winner);
-        let round_score = choice_score + outcome_score;
-        score += round_score;
+    let mut score = 0;
+    for (opponent_choice, your_choice) in rounds {
+        score += rules.get_round_score(opponent_choice, your_choice);
     }
 
     return Ok(format!("{}", score));
 }
 
-fn parse_outcome(choice: &str) -> Result<Winner, String> {
-    return match choice {
-        "X" => Ok(Winner::Opponent),
-        "Y" => Ok(Winner::Tied),
-        "Z" => Ok(Winner::You),
-        _ => Err(format!("Invalid outcome: '{}'", choice))
-    }
+pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
+    return solve_part_1_with_rules(custom_input, &get_default_ruleset());
 }
 
-fn parse_line(line: &str) -> Result<(Choice, Winner), String> {
-    let opponents_choice: Choice;
+fn parse_line(line: &str, rules: &Ruleset) -> Result<(usize, Winner), String> {
+    let opponents_choice: usize;
     let outcome: Winner;
 
     match line.split_once(" ") {
         Some((theirs, yours)) => {
-            let parsed_opponent_choice = parse_opponent_choice(theirs);
-            let parsed_outcome = parse_outcome(yours);
+            let parsed_opponent_choice = rules.parse_opponent_choice(theirs);
+            let parsed_outcome = rules.parse_outcome(yours);
             match (parsed_opponent_choice, parsed_outcome) {
                 (Ok(valid_opponent_choice), Ok(valid_outcome)) => {
                     opponents_choice = valid_opponent_choice;
@@ -150,41 +385,367 @@ fn parse_line(line: &str) -> Result<(Choice, Winner), String> {
     return Ok((opponents_choice, outcome));
 }
 
-fn get_your_choice(opponent_choice: &Choice, outcome: &Winner) -> Choice {
-    return match (opponent_choice, outcome) {
-        (choice, Winner::Tied) => choice.clone(),
-        (Choice::Rock, Winner::Opponent) => Choice::Scissors,
-        (Choice::Rock, Winner::You) => Choice::Paper,
-        (Choice::Paper, Winner::Opponent) => Choice::Rock,
-        (Choice::Paper, Winner::You) => Choice::Scissors,
-        (Choice::Scissors, Winner::Opponent) => Choice::Paper,
-        (Choice::Scissors, Winner::You) => Choice::Rock,
+pub fn solve_part_2_with_rules(custom_input: Option<String>, rules: &Ruleset) -> Result<String, String> {
+    let input = get_input(custom_input);
+
+    let mut rounds = Vec::new();
+    for line in input.lines() {
+        match parse_line(line, rules) {
+            Ok(round) => rounds.push(round),
+            Err(error) => return Err(error)
+        }
     }
+    stats::mark_parsed();
+
+    let mut score = 0;
+    for (opponent_choice, outcome) in rounds {
+        let your_choice = rules.get_your_choice(opponent_choice, &outcome);
+        score += rules.get_round_score(opponent_choice, your_choice);
+    }
+
+    return Ok(format!("{}", score));
 }
 
 pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
-    let input = get_input(custom_input);
+    return solve_part_2_with_rules(custom_input, &get_default_ruleset());
+}
 
-    let mut score = 0;
+// Trying every choice mapping takes shapes! scores, which stops being useful quickly.
+static MAX_MAPPING_SHAPES: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnMeaning {
+    Choice,
+    Outcome,
+}
+
+#[derive(Debug, Clone)]
+pub struct MappingScore {
+    pub meaning: ColumnMeaning,
+    pub mapping: Vec<(String, String)>,
+    pub score: i64,
+    pub best: bool,
+    pub worst: bool,
+}
+
+fn get_outcome_name(outcome: &Winner) -> &'static str {
+    return match outcome {
+        Winner::Opponent => "Lose",
+        Winner::Tied => "Draw",
+        Winner::You => "Win",
+    }
+}
+
+// Every ordering of 0..count, in lexicographic order so the identity comes first.
+fn get_permutations(count: usize) -> Vec<Vec<usize>> {
+    if count == 0 {
+        return vec![vec![]];
+    }
+    let mut permutations = Vec::new();
+    for first in 0..count {
+        for rest in get_permutations(count - 1) {
+            let mut permutation = vec![first];
+            permutation.extend(rest.iter().map(|index| if *index >= first { index + 1 } else { *index }));
+            permutations.push(permutation);
+        }
+    }
+    return permutations;
+}
+
+fn parse_guide(input: &str, rules: &Ruleset, symbols: &[String]) -> Result<Vec<(usize, usize)>, String> {
+    let mut rounds = Vec::new();
     for line in input.lines() {
-        let opponent_choice: Choice;
-        let outcome: Winner;
+        match line.split_once(" ") {
+            Some((theirs, column)) => {
+                let parsed_column = match symbols.iter().position(|symbol| symbol == column) {
+                    Some(index) => Ok(index),
+                    None => Err(format!("Invalid column: '{}'", column)),
+                };
+                match (rules.parse_opponent_choice(theirs), parsed_column) {
+                    (Ok(opponent_choice), Ok(index)) => rounds.push((opponent_choice, index)),
+                    (Err(error), ..) => return Err(error),
+                    (.., Err(error)) => return Err(error),
+                }
+            }
+            None => return Err(format!("Unable to split line '{}'", line)),
+        }
+    }
+    return Ok(rounds);
+}
+
+// Scores the guide with the second column read as each ordering of the shapes, then as each
+// ordering of the outcomes. A reading is left out if the guide uses symbols it doesn't have. The
+// highest and lowest totals across all readings are marked.
+pub fn get_mapping_scores_with_rules(custom_input: Option<String>, rules: &Ruleset) -> Result<Vec<MappingScore>, String> {
+    let input = get_input(custom_input);
+    let your_symbols: Vec<String> = rules.shapes.iter().map(|shape| shape.your_symbol.clone()).collect();
 
-        match parse_line(line) {
-            Ok((them, parsed_outcome)) => {
-                opponent_choice = them;
-                outcome = parsed_outcome;
+    let mut scores = Vec::new();
+    let mut first_error = None;
+    for (meaning, symbols) in [(ColumnMeaning::Choice, your_symbols.as_slice()), (ColumnMeaning::Outcome, &rules.outcome_symbols)] {
+        let rounds = match parse_guide(&input, rules, symbols) {
+            Ok(rounds) => rounds,
+            Err(error) => {
+                first_error.get_or_insert(error);
+                continue;
             }
-            Err(error) => return Err(error)
+        };
+        if meaning == ColumnMeaning::Choice && symbols.len() > MAX_MAPPING_SHAPES {
+            return Err(format!("Too many shapes to try every mapping: '{}'", symbols.len()));
         }
 
+        for permutation in get_permutations(symbols.len()) {
+            let mut score = 0;
+            for (opponent_choice, column) in &rounds {
+                let your_choice = match meaning {
+                    ColumnMeaning::Choice => permutation[*column],
+                    ColumnMeaning::Outcome => rules.get_your_choice(*opponent_choice, &OUTCOMES[permutation[*column]]),
+                };
+                score += rules.get_round_score(*opponent_choice, your_choice);
+            }
+            let mapping = symbols
+                .iter()
+                .zip(permutation.iter())
+                .map(|(symbol, index)| {
+                    let meaning_name = match meaning {
+                        ColumnMeaning::Choice => rules.shapes[*index].name.clone(),
+                        ColumnMeaning::Outcome => get_outcome_name(&OUTCOMES[*index]).to_string(),
+                    };
+                    (symbol.clone(), meaning_name)
+                })
+                .collect();
+            scores.push(MappingScore { meaning, mapping, score, best: false, worst: false });
+        }
+    }
+    if scores.is_empty() {
+        return Err(first_error.unwrap());
+    }
+
+    let best = scores.iter().map(|entry| entry.score).max().unwrap_or(0);
+    let worst = scores.iter().map(|entry| entry.score).min().unwrap_or(0);
+    for entry in scores.iter_mut() {
+        entry.best = entry.score == best;
+        entry.worst = entry.score == worst;
+    }
+
+    return Ok(scores);
+}
+
+pub fn get_mapping_scores(custom_input: Option<String>) -> Result<Vec<MappingScore>, String> {
+    return get_mapping_scores_with_rules(custom_input, &get_default_ruleset());
+}
 
-        let your_choice = get_your_choice(&opponent_choice, &outcome);
-        let choice_score = get_choice_score(&your_choice);
-        let outcome_score = get_outcome_score(&outcome);
-        let round_score = choice_score + outcome_score;
-        score += round_score;
+pub fn get_mapping_scores_table(custom_input: Option<String>, rules: &Ruleset) -> Result<Table, String> {
+    let scores = match get_mapping_scores_with_rules(custom_input, rules) {
+        Ok(result) => result,
+        Err(error) => return Err(error),
+    };
+
+    let mut table = Table::new(&[
+        ("Meaning", Alignment::Left),
+        ("Mapping", Alignment::Left),
+        ("Score", Alignment::Right),
+        ("Note", Alignment::Left),
+    ]);
+    for entry in scores {
+        let meaning = match entry.meaning {
+            ColumnMeaning::Choice => "choice",
+            ColumnMeaning::Outcome => "outcome",
+        };
+        let mapping: Vec<String> = entry
+            .mapping
+            .iter()
+            .map(|(symbol, meaning_name)| format!("{}={}", symbol, meaning_name))
+            .collect();
+        let note = match (entry.best, entry.worst) {
+            (true, true) => "best, worst",
+            (true, false) => "best",
+            (false, true) => "worst",
+            (false, false) => "",
+        };
+        table.add_row(vec![meaning.to_string(), mapping.join(" "), entry.score.to_string(), note.to_string()]);
     }
 
-    return Ok(format!("{}", score));
+    return Ok(table);
+}
+
+#[derive(Debug, Clone)]
+pub struct RoundScore {
+    pub round: usize,
+    pub opponent_choice: String,
+    pub your_choice: String,
+    pub outcome: String,
+    pub choice_score: i64,
+    pub outcome_score: i64,
+    pub round_score: i64,
+    pub running_total: i64,
+}
+
+// Scores each line of the guide the way the given part reads it. The last running total is the
+// part's answer.
+pub fn get_round_breakdown_with_rules(custom_input: Option<String>, rules: &Ruleset, part: Part) -> Result<Vec<RoundScore>, String> {
+    let input = get_input(custom_input);
+
+    let mut rounds = Vec::new();
+    let mut running_total = 0;
+    for (index, line) in input.lines().enumerate() {
+        let parsed = match part {
+            Part::Part1 => parse_line_incorrectly(line, rules).map(|(them, you)| (them, you, rules.get_winner(them, you))),
+            Part::Part2 => parse_line(line, rules).map(|(them, outcome)| (them, rules.get_your_choice(them, &outcome), outcome)),
+        };
+        let (opponent_choice, your_choice, outcome) = match parsed {
+            Ok(round) => round,
+            Err(error) => return Err(error),
+        };
+
+        let choice_score = rules.shapes[your_choice].score;
+        let outcome_score = rules.get_outcome_score(&outcome);
+        running_total += choice_score + outcome_score;
+        rounds.push(RoundScore {
+            round: index + 1,
+            opponent_choice: rules.shapes[opponent_choice].name.clone(),
+            your_choice: rules.shapes[your_choice].name.clone(),
+            outcome: get_outcome_name(&outcome).to_string(),
+            choice_score,
+            outcome_score,
+            round_score: choice_score + outcome_score,
+            running_total,
+        });
+    }
+
+    return Ok(rounds);
+}
+
+pub fn get_round_breakdown(custom_input: Option<String>, part: Part) -> Result<Vec<RoundScore>, String> {
+    return get_round_breakdown_with_rules(custom_input, &get_default_ruleset(), part);
+}
+
+// Both parts' readings in one table, with the running total restarting for part 2.
+pub fn get_round_breakdown_table(custom_input: Option<String>, rules: &Ruleset) -> Result<Table, String> {
+    let mut table = Table::new(&[
+        ("Part", Alignment::Right),
+        ("Round", Alignment::Right),
+        ("Opponent", Alignment::Left),
+        ("You", Alignment::Left),
+        ("Outcome", Alignment::Left),
+        ("Choice score", Alignment::Right),
+        ("Outcome score", Alignment::Right),
+        ("Round score", Alignment::Right),
+        ("Running total", Alignment::Right),
+    ]);
+    for part in [Part::Part1, Part::Part2] {
+        let rounds = match get_round_breakdown_with_rules(custom_input.clone(), rules, part) {
+            Ok(result) => result,
+            Err(error) => return Err(format!("Part {}: {}", part.number(), error)),
+        };
+        for round in rounds {
+            table.add_row(vec![
+                part.number().to_string(),
+                round.round.to_string(),
+                round.opponent_choice,
+                round.your_choice,
+                round.outcome,
+                round.choice_score.to_string(),
+                round.outcome_score.to_string(),
+                round.round_score.to_string(),
+                round.running_total.to_string(),
+            ]);
+        }
+    }
+
+    return Ok(table);
+}
+
+#[derive(Debug, Clone)]
+pub struct OpponentAnalysis {
+    pub shapes: Vec<String>,
+    pub frequencies: Vec<usize>,
+    // transitions[previous][next] counts how often the opponent played `next` straight after `previous`
+    pub transitions: Vec<Vec<usize>>,
+    pub guide_score: i64,
+    pub max_score: i64,
+    pub fixed_scores: Vec<i64>,
+    pub best_fixed_choice: usize,
+}
+
+// Compares the guide, read as in part 1, against the best response to every round and against
+// always playing the same shape.
+pub fn get_opponent_analysis_with_rules(custom_input: Option<String>, rules: &Ruleset) -> Result<OpponentAnalysis, String> {
+    let input = get_input(custom_input);
+    let shape_count = rules.shapes.len();
+
+    let mut analysis = OpponentAnalysis {
+        shapes: rules.shapes.iter().map(|shape| shape.name.clone()).collect(),
+        frequencies: vec![0; shape_count],
+        transitions: vec![vec![0; shape_count]; shape_count],
+        guide_score: 0,
+        max_score: 0,
+        fixed_scores: vec![0; shape_count],
+        best_fixed_choice: 0,
+    };
+    let mut previous_choice: Option<usize> = None;
+    for line in input.lines() {
+        let (opponent_choice, your_choice) = match parse_line_incorrectly(line, rules) {
+            Ok(round) => round,
+            Err(error) => return Err(error),
+        };
+
+        analysis.frequencies[opponent_choice] += 1;
+        if let Some(previous) = previous_choice {
+            analysis.transitions[previous][opponent_choice] += 1;
+        }
+        previous_choice = Some(opponent_choice);
+
+        analysis.guide_score += rules.get_round_score(opponent_choice, your_choice);
+        let round_scores: Vec<i64> = (0..shape_count).map(|choice| rules.get_round_score(opponent_choice, choice)).collect();
+        analysis.max_score += round_scores.iter().max().unwrap();
+        for (choice, score) in round_scores.iter().enumerate() {
+            analysis.fixed_scores[choice] += score;
+        }
+    }
+
+    for choice in 1..shape_count {
+        if analysis.fixed_scores[choice] > analysis.fixed_scores[analysis.best_fixed_choice] {
+            analysis.best_fixed_choice = choice;
+        }
+    }
+
+    return Ok(analysis);
+}
+
+pub fn get_opponent_analysis(custom_input: Option<String>) -> Result<OpponentAnalysis, String> {
+    return get_opponent_analysis_with_rules(custom_input, &get_default_ruleset());
+}
+
+pub fn get_opponent_analysis_tables(custom_input: Option<String>, rules: &Ruleset) -> Result<Vec<Table>, String> {
+    let analysis = match get_opponent_analysis_with_rules(custom_input, rules) {
+        Ok(result) => result,
+        Err(error) => return Err(error),
+    };
+    let rounds: usize = analysis.frequencies.iter().sum();
+
+    let mut frequencies = Table::new(&[("Opponent", Alignment::Left), ("Rounds", Alignment::Right), ("Share", Alignment::Right)]);
+    for (shape, count) in analysis.shapes.iter().zip(analysis.frequencies.iter()) {
+        let share = if rounds == 0 { 0.0 } else { *count as f64 * 100.0 / rounds as f64 };
+        frequencies.add_row(vec![shape.clone(), count.to_string(), format!("{:.1}%", share)]);
+    }
+
+    let mut transition_columns = vec![("After", Alignment::Left)];
+    transition_columns.extend(analysis.shapes.iter().map(|shape| (shape.as_str(), Alignment::Right)));
+    let mut transitions = Table::new(&transition_columns);
+    for (shape, counts) in analysis.shapes.iter().zip(analysis.transitions.iter()) {
+        let mut row = vec![shape.clone()];
+        row.extend(counts.iter().map(|count| count.to_string()));
+        transitions.add_row(row);
+    }
+
+    let mut strategies = Table::new(&[("Strategy", Alignment::Left), ("Score", Alignment::Right), ("Note", Alignment::Left)]);
+    strategies.add_row(vec!["Guide (part 1)".to_string(), analysis.guide_score.to_string(), String::new()]);
+    strategies.add_row(vec!["Best response each round".to_string(), analysis.max_score.to_string(), "maximum".to_string()]);
+    for (choice, score) in analysis.fixed_scores.iter().enumerate() {
+        let note = if choice == analysis.best_fixed_choice { "best fixed counter" } else { "" };
+        strategies.add_row(vec![format!("Always {}", analysis.shapes[choice]), score.to_string(), note.to_string()]);
+    }
+
+    return Ok(vec![frequencies, transitions, strategies]);
 }
@@ -1,5 +1,13 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::report::{Alignment, Table};
+use crate::stats;
+
 pub static EXAMPLE_INPUT: &str = include_str!("../example_input/day3.txt");
 
+pub static SOLVER_VERSION: u32 = 2;
+
 fn get_input(custom_input: Option<String>) -> String {
     return match custom_input {
         Some(custom_input) => custom_input,
@@ -15,76 +23,517 @@ fn get_priority(item: char) -> Result<u32, String> {
     };
 }
 
-fn get_unique_common_characters(a: &str, b: &str) -> String {
-    let mut results = String::new();
-    for item in a.chars() {
-        if b.contains(item) && !results.contains(item) {
-            results.push(item);
+fn get_item(priority: u32) -> char {
+    return match priority {
+        1..=26 => (b'a' + (priority - 1) as u8) as char,
+        _ => (b'A' + (priority - 27) as u8) as char,
+    };
+}
+
+// A set of items, stored as a bitmask where bit n is set if the item with priority n is present.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemSet {
+    bits: u64,
+}
+
+impl ItemSet {
+    pub fn new() -> ItemSet {
+        return ItemSet { bits: 0 };
+    }
+
+    pub fn from_items(items: &str) -> Result<ItemSet, String> {
+        let mut set = ItemSet::new();
+        for item in items.chars() {
+            match get_priority(item) {
+                Ok(priority) => set.bits |= 1 << priority,
+                Err(error) => return Err(error),
+            }
         }
+        return Ok(set);
+    }
+
+    pub fn contains(&self, item: char) -> bool {
+        return match get_priority(item) {
+            Ok(priority) => self.bits & (1 << priority) != 0,
+            Err(_) => false,
+        };
+    }
+
+    pub fn intersection(&self, other: &ItemSet) -> ItemSet {
+        return ItemSet { bits: self.bits & other.bits };
+    }
+
+    pub fn union(&self, other: &ItemSet) -> ItemSet {
+        return ItemSet { bits: self.bits | other.bits };
+    }
+
+    pub fn len(&self) -> usize {
+        return self.bits.count_ones() as usize;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.bits == 0;
+    }
+
+    pub fn priority_sum(&self) -> u32 {
+        let mut sum = 0;
+        let mut remaining = self.bits;
+        while remaining != 0 {
+            sum += remaining.trailing_zeros();
+            remaining &= remaining - 1;
+        }
+        return sum;
+    }
+
+    // Items in priority order.
+    pub fn items(&self) -> Vec<char> {
+        return (1..=52).filter(|priority| self.bits & (1 << priority) != 0).map(get_item).collect();
     }
-    return results;
+}
+
+impl fmt::Display for ItemSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.items().iter().collect::<String>());
+    }
+}
+
+pub static DEFAULT_COMPARTMENTS: usize = 2;
+pub static DEFAULT_GROUP_SIZE: usize = 3;
+
+fn get_compartment_length(line: &str, compartments: usize) -> Result<usize, String> {
+    let length = line.len();
+    let compartment_length = length / compartments;
+    if !length.is_multiple_of(compartments) || (0..=compartments).any(|index| !line.is_char_boundary(index * compartment_length)) {
+        return Err(format!("Line cannot be evenly split into {} compartments: '{}'", compartments, line));
+    }
+    return Ok(compartment_length);
+}
+
+// The items in each of `compartments` equal parts of the rucksack.
+fn get_compartment_items(line: &str, compartments: usize) -> Result<Vec<ItemSet>, String> {
+    let compartment_length = match get_compartment_length(line, compartments) {
+        Ok(length) => length,
+        Err(error) => return Err(error),
+    };
+
+    let mut compartment_items = Vec::with_capacity(compartments);
+    for index in 0..compartments {
+        let compartment = &line[index * compartment_length..(index + 1) * compartment_length];
+        match ItemSet::from_items(compartment) {
+            Ok(items) => compartment_items.push(items),
+            Err(error) => return Err(error),
+        }
+    }
+    return Ok(compartment_items);
+}
+
+fn get_shared_items(compartment_items: &[ItemSet]) -> ItemSet {
+    let mut common: Option<ItemSet> = None;
+    for items in compartment_items {
+        common = Some(match common {
+            Some(common) => common.intersection(items),
+            None => *items,
+        });
+    }
+    return common.unwrap_or_default();
+}
+
+// Items found in every one of `compartments` equal parts of the rucksack.
+fn get_common_items(line: &str, compartments: usize) -> Result<ItemSet, String> {
+    return match get_compartment_items(line, compartments) {
+        Ok(compartment_items) => Ok(get_shared_items(&compartment_items)),
+        Err(error) => Err(error),
+    };
+}
+
+pub fn solve_part_1_with_compartments(custom_input: Option<String>, compartments: usize) -> Result<String, String> {
+    if compartments == 0 {
+        return Err("Must split rucksacks into at least one compartment".to_string());
+    }
+
+    let input = get_input(custom_input);
+
+    let mut rucksacks = Vec::new();
+    for line in input.lines() {
+        match get_compartment_items(line, compartments) {
+            Ok(compartment_items) => rucksacks.push(compartment_items),
+            Err(error) => return Err(error),
+        }
+    }
+    stats::mark_parsed();
+
+    let mut score: u64 = 0;
+    for compartment_items in &rucksacks {
+        score += get_shared_items(compartment_items).priority_sum() as u64;
+    }
+
+    return Ok(format!("{}", score));
 }
 
 pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
+    return solve_part_1_with_compartments(custom_input, DEFAULT_COMPARTMENTS);
+}
+
+pub fn solve_part_2_with_group_size(custom_input: Option<String>, group_size: usize) -> Result<String, String> {
+    if group_size == 0 {
+        return Err("Groups must have at least one elf".to_string());
+    }
+
     let input = get_input(custom_input);
 
-    let mut score: u32 = 0;
+    let mut rucksacks = Vec::new();
     for line in input.lines() {
-        let length = line.len();
-        if length % 2 != 0 {
-            return Err(format!("Line cannot be evenly split: '{}'", line))
+        match ItemSet::from_items(line) {
+            Ok(items) => rucksacks.push((line, items)),
+            Err(error) => return Err(error),
         }
-        let (first, second) = line.split_at(length / 2);
+    }
+    stats::mark_parsed();
 
-        let common = get_unique_common_characters(first, second);
+    let mut score: u64 = 0;
+    let mut group_length = 0;
+    let mut group_start = "";
+    let mut common = ItemSet::new();
+    for (line, items) in rucksacks {
+        if group_length == 0 {
+            group_start = line;
+            common = items;
+        } else {
+            common = common.intersection(&items);
+        }
+        group_length += 1;
 
-        for item in common.chars() {
-            match get_priority(item) {
-                Ok(item_score) => score += item_score,
-                Err(error) => return Err(error),
-            }
+        if group_length < group_size {
+            continue;
         }
+
+        if common.is_empty() {
+            return Err(format!("No badge found for group of {} starting with '{}'", group_size, group_start));
+        } else if common.len() > 1 {
+            return Err(format!(
+                "Multiple badges found ('{}') in group of {} starting with '{}'",
+                common, group_size, group_start
+            ));
+        }
+
+        score += common.priority_sum() as u64;
+        group_length = 0;
+    }
+
+    if group_length > 0 {
+        return Err(format!(
+            "Incomplete group starting with '{}', found {} of {} elves",
+            group_start, group_length, group_size
+        ))
     }
 
     return Ok(format!("{}", score));
 }
 
 pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
+    return solve_part_2_with_group_size(custom_input, DEFAULT_GROUP_SIZE);
+}
+
+#[derive(Debug, Clone)]
+pub struct RucksackReportEntry {
+    pub line: usize,
+    pub compartments: Vec<String>,
+    pub shared: ItemSet,
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupReportEntry {
+    pub group: usize,
+    pub lines: Vec<usize>,
+    pub badges: ItemSet,
+    pub complete: bool,
+}
+
+// Lines are numbered from 1. Unlike the solvers, rucksacks and groups that don't share exactly
+// one item are listed rather than rejected, so that mistakes in the input can be found.
+pub fn get_rucksack_report(custom_input: Option<String>, compartments: usize) -> Result<Vec<RucksackReportEntry>, String> {
+    if compartments == 0 {
+        return Err("Must split rucksacks into at least one compartment".to_string());
+    }
     let input = get_input(custom_input);
 
-    let mut badges = String::new();
-    let mut group = vec![];
-    for line in input.lines() {
-        group.push(line);
+    let mut entries = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let compartment_length = match get_compartment_length(line, compartments) {
+            Ok(length) => length,
+            Err(error) => return Err(error),
+        };
+        let shared = match get_common_items(line, compartments) {
+            Ok(shared) => shared,
+            Err(error) => return Err(error),
+        };
+        entries.push(RucksackReportEntry {
+            line: index + 1,
+            compartments: (0..compartments)
+                .map(|part| line[part * compartment_length..(part + 1) * compartment_length].to_string())
+                .collect(),
+            shared,
+        });
+    }
+    return Ok(entries);
+}
 
-        if group.len() < 3 {
-            continue;
+pub fn get_group_report(custom_input: Option<String>, group_size: usize) -> Result<Vec<GroupReportEntry>, String> {
+    if group_size == 0 {
+        return Err("Groups must have at least one elf".to_string());
+    }
+    let input = get_input(custom_input);
+
+    let mut entries: Vec<GroupReportEntry> = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let items = match ItemSet::from_items(line) {
+            Ok(items) => items,
+            Err(error) => return Err(error),
+        };
+        match entries.last_mut() {
+            Some(entry) if !entry.complete => {
+                entry.lines.push(index + 1);
+                entry.badges = entry.badges.intersection(&items);
+                entry.complete = entry.lines.len() == group_size;
+            }
+            _ => entries.push(GroupReportEntry {
+                group: entries.len() + 1,
+                lines: vec![index + 1],
+                badges: items,
+                complete: group_size == 1,
+            }),
         }
+    }
+    return Ok(entries);
+}
 
-        let initial_common = get_unique_common_characters(group[0], group[1]);
-        let common = get_unique_common_characters(&initial_common, group[2]);
+fn get_shared_note(shared: &ItemSet) -> &'static str {
+    return match shared.len() {
+        0 => "nothing shared",
+        1 => "",
+        _ => "more than one shared item",
+    };
+}
 
-        if common.len() == 0 {
-            return Err(format!("No badge found for group starting with '{}'", group[0]));
-        } else if common.len() > 1 {
-            return Err(format!("Multiple badges found ('{}') in group starting with '{}'", common, group[0]));
-        }
+pub fn get_report_tables(custom_input: Option<String>, compartments: usize, group_size: usize) -> Result<Vec<Table>, String> {
+    let rucksacks = match get_rucksack_report(custom_input.clone(), compartments) {
+        Ok(result) => result,
+        Err(error) => return Err(error),
+    };
+    let groups = match get_group_report(custom_input, group_size) {
+        Ok(result) => result,
+        Err(error) => return Err(error),
+    };
 
-        badges.push_str(common.as_str());
-        group.clear();
+    let mut rucksack_table = Table::new(&[
+        ("Line", Alignment::Right),
+        ("Compartments", Alignment::Left),
+        ("Shared", Alignment::Left),
+        ("Priority", Alignment::Right),
+        ("Note", Alignment::Left),
+    ]);
+    for entry in rucksacks {
+        rucksack_table.add_row(vec![
+            entry.line.to_string(),
+            entry.compartments.join(" "),
+            entry.shared.to_string(),
+            entry.shared.priority_sum().to_string(),
+            get_shared_note(&entry.shared).to_string(),
+        ]);
     }
 
-    if group.len() > 0 {
-        return Err(format!("Incomplete group starting with '{}'", group[0]))
+    let mut group_table = Table::new(&[
+        ("Group", Alignment::Right),
+        ("Lines", Alignment::Left),
+        ("Badge", Alignment::Left),
+        ("Priority", Alignment::Right),
+        ("Note", Alignment::Left),
+    ]);
+    for entry in groups {
+        let lines: Vec<String> = entry.lines.iter().map(|line| line.to_string()).collect();
+        let note = if entry.complete { get_shared_note(&entry.badges) } else { "incomplete group" };
+        group_table.add_row(vec![
+            entry.group.to_string(),
+            lines.join(" "),
+            entry.badges.to_string(),
+            entry.badges.priority_sum().to_string(),
+            note.to_string(),
+        ]);
     }
 
-    let mut score: u32 = 0;
-    for item in badges.chars() {
+    return Ok(vec![rucksack_table, group_table]);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemMove {
+    pub item: char,
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RucksackPlan {
+    pub line: usize,
+    // None if no arrangement keeps the compartments equal-sized, e.g. if one item type fills more
+    // than a compartment.
+    pub moves: Option<Vec<ItemMove>>,
+}
+
+impl RucksackPlan {
+    pub fn items_moved(&self) -> usize {
+        return self.moves.iter().flatten().map(|item_move| item_move.count).sum();
+    }
+
+    // Moving an item costs its priority.
+    pub fn effort(&self) -> u64 {
+        return self
+            .moves
+            .iter()
+            .flatten()
+            .map(|item_move| item_move.count as u64 * get_priority(item_move.item).unwrap() as u64)
+            .sum();
+    }
+}
+
+// The most compartment fills the planner keeps across all types. Finding an exact fill is a
+// partition problem, so the number of fills grows exponentially with the compartment count.
+static MAX_PLAN_STATES: usize = 2_000_000;
+
+// Every item type ends up in exactly one compartment, so a plan is an assignment of types to
+// compartments whose totals fill each compartment exactly. Items of a type that aren't already in
+// its compartment are moved there. Searches all assignments compartment fill by compartment fill,
+// keeping the fewest items moved, then the least effort. Gives up with an error rather than
+// exceed MAX_PLAN_STATES fills.
+fn plan_rucksack(line: &str, compartments: usize) -> Result<Option<Vec<ItemMove>>, String> {
+    let compartment_length = match get_compartment_length(line, compartments) {
+        Ok(length) => length,
+        Err(error) => return Err(error),
+    };
+
+    // counts[priority][compartment]
+    let mut counts = vec![vec![0usize; compartments]; 53];
+    for (index, item) in line.chars().enumerate() {
         match get_priority(item) {
-            Ok(item_score) => score += item_score,
+            Ok(priority) => counts[priority as usize][index / compartment_length] += 1,
             Err(error) => return Err(error),
         }
     }
+    let mut types: Vec<usize> = (1..=52).filter(|priority| counts[*priority].iter().sum::<usize>() > 0).collect();
+    // placing the most common types first keeps the number of distinct fills down
+    types.sort_by_key(|priority| std::cmp::Reverse(counts[*priority].iter().sum::<usize>()));
 
-    return Ok(format!("{}", score));
+    // Compartment fills are packed into one number, with compartment n's fill as digit n in base
+    // compartment_length + 1.
+    let radix = compartment_length as u64 + 1;
+    let place_values: Vec<u64> = match (0..compartments as u32).map(|power| radix.checked_pow(power)).collect() {
+        Some(place_values) => place_values,
+        None => return Err(format!("Too many compartments to plan: '{}'", compartments)),
+    };
+    if radix.checked_pow(compartments as u32).is_none() {
+        return Err(format!("Too many compartments to plan: '{}'", compartments));
+    }
+
+    // layers[n] maps the fills after placing the first n types to (items moved, effort, previous
+    // fills, compartment chosen for type n - 1)
+    type Layer = HashMap<u64, (usize, u64, u64, usize)>;
+    let mut layers: Vec<Layer> = vec![HashMap::from([(0, (0, 0, 0, 0))])];
+    let mut states = 1;
+    for priority in &types {
+        let total: usize = counts[*priority].iter().sum();
+        let mut next_layer: Layer = HashMap::new();
+        for (fills, (moved, effort, _, _)) in layers.last().unwrap() {
+            for compartment in 0..compartments {
+                let fill = (fills / place_values[compartment] % radix) as usize;
+                if fill + total > compartment_length {
+                    continue;
+                }
+                let next_fills = fills + total as u64 * place_values[compartment];
+                let type_moved = total - counts[*priority][compartment];
+                let candidate = (moved + type_moved, effort + (type_moved * priority) as u64, *fills, compartment);
+                match next_layer.get(&next_fills) {
+                    // ties are broken on the rest of the tuple, so plans don't depend on hash order
+                    Some(existing) if *existing <= candidate => (),
+                    _ => {
+                        next_layer.insert(next_fills, candidate);
+                    }
+                }
+            }
+            if states + next_layer.len() > MAX_PLAN_STATES {
+                return Err(format!("Too many arrangements to plan {} compartments: '{}'", compartments, line));
+            }
+        }
+        states += next_layer.len();
+        layers.push(next_layer);
+    }
+
+    let mut fills: u64 = place_values.iter().map(|place_value| compartment_length as u64 * place_value).sum();
+    if !layers.last().unwrap().contains_key(&fills) {
+        return Ok(None);
+    }
+    let mut moves = Vec::new();
+    for (index, priority) in types.iter().enumerate().rev() {
+        let (_, _, previous_fills, compartment) = *layers[index + 1].get(&fills).unwrap();
+        for (from, count) in counts[*priority].iter().enumerate() {
+            if from != compartment && *count > 0 {
+                moves.push(ItemMove {
+                    item: get_item(*priority as u32),
+                    count: *count,
+                    from: from + 1,
+                    to: compartment + 1,
+                });
+            }
+        }
+        fills = previous_fills;
+    }
+    moves.sort_by_key(|item_move| get_priority(item_move.item).unwrap());
+    return Ok(Some(moves));
+}
+
+pub fn get_reorganization_plan(custom_input: Option<String>, compartments: usize) -> Result<Vec<RucksackPlan>, String> {
+    if compartments == 0 {
+        return Err("Must split rucksacks into at least one compartment".to_string());
+    }
+    let input = get_input(custom_input);
+
+    let mut plans = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        match plan_rucksack(line, compartments) {
+            Ok(moves) => plans.push(RucksackPlan { line: index + 1, moves }),
+            Err(error) => return Err(error),
+        }
+    }
+    return Ok(plans);
+}
+
+pub fn get_reorganization_plan_table(custom_input: Option<String>, compartments: usize) -> Result<Table, String> {
+    let plans = match get_reorganization_plan(custom_input, compartments) {
+        Ok(result) => result,
+        Err(error) => return Err(error),
+    };
+
+    let mut table = Table::new(&[
+        ("Line", Alignment::Right),
+        ("Moves", Alignment::Left),
+        ("Items moved", Alignment::Right),
+        ("Effort", Alignment::Right),
+    ]);
+    let mut total_moved = 0;
+    let mut total_effort = 0;
+    for plan in plans {
+        let moves = match &plan.moves {
+            Some(moves) => moves
+                .iter()
+                .map(|item_move| format!("{}x{} {}->{}", item_move.count, item_move.item, item_move.from, item_move.to))
+                .collect::<Vec<String>>()
+                .join(", "),
+            None => "no equal-sized arrangement".to_string(),
+        };
+        total_moved += plan.items_moved();
+        total_effort += plan.effort();
+        table.add_row(vec![plan.line.to_string(), moves, plan.items_moved().to_string(), plan.effort().to_string()]);
+    }
+    table.add_row(vec!["Total".to_string(), String::new(), total_moved.to_string(), total_effort.to_string()]);
+
+    return Ok(table);
 }
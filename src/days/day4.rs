@@ -1,5 +1,14 @@
+use std::collections::BTreeMap;
+use std::num::IntErrorKind;
+
+use crate::interval::{Interval, IntervalSet};
+use crate::report::{Alignment, Table};
+use crate::stats;
+
 pub static EXAMPLE_INPUT: &str = include_str!("../example_input/day4.txt");
 
+pub static SOLVER_VERSION: u32 = 4;
+
 fn get_input(custom_input: Option<String>) -> String {
     return match custom_input {
         Some(custom_input) => custom_input,
@@ -7,52 +16,68 @@ fn get_input(custom_input: Option<String>) -> String {
     };
 }
 
-struct Range {
-    start: u32,
-    end: u32,
-}
+type Range = Interval<u32>;
 
-impl Range {
-    fn contains(&self, range: &Range) -> bool {
-        return self.start <= range.start && self.end >= range.end;
-    }
+// What to do with a range whose start is after its end, such as '9-3'. An empty range covers no
+// sections, so it neither contains nor overlaps another range, and is left out of the analysis
+// modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReversedRangePolicy {
+    #[default]
+    Reject,
+    Normalize,
+    Empty,
+}
 
-    fn overlaps(&self, range: &Range) -> bool {
-        return self.start <= range.end && self.end >= range.start;
-    }
+fn parse_bound(raw_bound: &str, field: &str, line_number: usize) -> Result<u32, String> {
+    return match raw_bound.parse::<u32>() {
+        Ok(bound) => Ok(bound),
+        Err(error) => {
+            let reason = match error.kind() {
+                IntErrorKind::Empty => "missing",
+                IntErrorKind::PosOverflow => "too large",
+                _ => "not a number",
+            };
+            Err(format!("Failed to parse {} on line {}, {}: '{}'", field, line_number, reason, raw_bound))
+        }
+    };
 }
 
-fn parse_range(raw_range: &str) -> Result<Range, String> {
+fn parse_range(raw_range: &str, elf: &str, line_number: usize, policy: ReversedRangePolicy) -> Result<Range, String> {
     let split_result = raw_range.split_once('-');
     if split_result.is_none() {
-        return Err(format!("Failed to split by dash: '{}'", raw_range));
+        return Err(format!("Failed to split {} range by dash on line {}: '{}'", elf, line_number, raw_range));
     }
     let (raw_start, raw_end) = split_result.unwrap();
 
-    let unchecked_start = raw_start.parse::<u32>();
-    let unchecked_end = raw_end.parse::<u32>();
+    let start = match parse_bound(raw_start, format!("start of {} range", elf).as_str(), line_number) {
+        Ok(start) => start,
+        Err(error) => return Err(error),
+    };
+    let end = match parse_bound(raw_end, format!("end of {} range", elf).as_str(), line_number) {
+        Ok(end) => end,
+        Err(error) => return Err(error),
+    };
 
-    if unchecked_start.is_err() {
-        return Err(format!("Failed to parse start of range '{}'", raw_range));
-    } else if unchecked_end.is_err() {
-        return Err(format!("Failed to parse end of range '{}'", raw_range));
+    if start <= end {
+        return Ok(Range::inclusive(start, end));
     }
-
-    let start = unchecked_start.unwrap();
-    let end = unchecked_end.unwrap();
-
-    return Ok(Range { start, end });
+    return match policy {
+        ReversedRangePolicy::Reject => Err(format!("Reversed {} range on line {}: '{}'", elf, line_number, raw_range)),
+        ReversedRangePolicy::Normalize => Ok(Range::inclusive(end, start)),
+        ReversedRangePolicy::Empty => Ok(Range::empty()),
+    };
 }
 
-fn get_range_pair(raw_pair: &str) -> Result<(Range, Range), String> {
+fn get_range_pair(raw_pair: &str, line_number: usize, policy: ReversedRangePolicy) -> Result<(Range, Range), String> {
     let split_result = raw_pair.split_once(',');
     if split_result.is_none() {
-        return Err(format!("Failed to split by comma: '{}'", raw_pair));
+        return Err(format!("Failed to split by comma on line {}: '{}'", line_number, raw_pair));
     }
     let (a, b) = split_result.unwrap();
 
-    let range_a_result = parse_range(a);
-    let range_b_result = parse_range(b);
+    let range_a_result = parse_range(a, "first", line_number, policy);
+    let range_b_result = parse_range(b, "second", line_number, policy);
 
     if range_a_result.is_err() {
         return Err(range_a_result.err().unwrap());
@@ -65,38 +90,344 @@ fn get_range_pair(raw_pair: &str) -> Result<(Range, Range), String> {
     return Ok((range_a, range_b));
 }
 
-pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
+fn get_range_pairs(input: &str, policy: ReversedRangePolicy) -> Result<Vec<(Range, Range)>, String> {
+    let mut range_pairs = Vec::new();
+    for (index, pair) in input.lines().enumerate() {
+        match get_range_pair(pair, index + 1, policy) {
+            Ok(range_pair) => range_pairs.push(range_pair),
+            Err(error) => return Err(error),
+        }
+    }
+    return Ok(range_pairs);
+}
+
+pub fn solve_part_1_with_policy(custom_input: Option<String>, policy: ReversedRangePolicy) -> Result<String, String> {
     let input = get_input(custom_input);
 
+    let range_pairs_result = get_range_pairs(&input, policy);
+    if range_pairs_result.is_err() {
+        return Err(range_pairs_result.err().unwrap());
+    }
+    let range_pairs = range_pairs_result.unwrap();
+    stats::mark_parsed();
+
     let mut count = 0;
-    for pair in input.lines() {
-        let range_pair_result = get_range_pair(pair);
-        if range_pair_result.is_err() {
-            return Err(range_pair_result.err().unwrap());
+    for (range_a, range_b) in range_pairs {
+        if range_a.is_empty() || range_b.is_empty() {
+            continue;
         }
-        let (range_a, range_b) = range_pair_result.unwrap();
-
-        if range_a.contains(&range_b) || range_b.contains(&range_a) {
+        if range_a.contains_interval(&range_b) || range_b.contains_interval(&range_a) {
             count += 1;
         }
     }
     return Ok(format!("{}", count));
 }
 
-pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
+pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
+    return solve_part_1_with_policy(custom_input, ReversedRangePolicy::default());
+}
+
+pub fn solve_part_2_with_policy(custom_input: Option<String>, policy: ReversedRangePolicy) -> Result<String, String> {
     let input = get_input(custom_input);
 
-    let mut count = 0;
-    for pair in input.lines() {
-        let range_pair_result = get_range_pair(pair);
-        if range_pair_result.is_err() {
-            return Err(range_pair_result.err().unwrap());
-        }
-        let (range_a, range_b) = range_pair_result.unwrap();
+    let range_pairs_result = get_range_pairs(&input, policy);
+    if range_pairs_result.is_err() {
+        return Err(range_pairs_result.err().unwrap());
+    }
+    let range_pairs = range_pairs_result.unwrap();
+    stats::mark_parsed();
 
+    let mut count = 0;
+    for (range_a, range_b) in range_pairs {
         if range_a.overlaps(&range_b) {
             count += 1;
         }
     }
     return Ok(format!("{}", count));
 }
+
+pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
+    return solve_part_2_with_policy(custom_input, ReversedRangePolicy::default());
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Assignment {
+    pub line: usize,
+    pub elf: usize,
+    pub sections: Range,
+}
+
+impl Assignment {
+    fn label(&self) -> String {
+        return format!("{}:{}", self.line, self.elf);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SweepAnalysis {
+    pub assignments: usize,
+    pub max_coverage: usize,
+    pub max_coverage_sections: IntervalSet<u32>,
+    // Runs of consecutive sections covered by the same number of elves, leaving out uncovered ones.
+    pub coverage: Vec<(Range, usize)>,
+    // Pairs of assignments from any lines where the first contains the second.
+    pub containments: Vec<(Assignment, Assignment)>,
+}
+
+// Lines are numbered from 1, and the elves on each line are 1 and 2. Empty assignments cover no
+// sections, so they're left out.
+fn get_assignments(input: &str, policy: ReversedRangePolicy) -> Result<Vec<Assignment>, String> {
+    let mut assignments = Vec::new();
+    for (index, pair) in input.lines().enumerate() {
+        let (range_a, range_b) = match get_range_pair(pair, index + 1, policy) {
+            Ok(ranges) => ranges,
+            Err(error) => return Err(error),
+        };
+        for (elf, sections) in [(1, range_a), (2, range_b)] {
+            if !sections.is_empty() {
+                assignments.push(Assignment { line: index + 1, elf, sections });
+            }
+        }
+    }
+    return Ok(assignments);
+}
+
+// Each assignment adds one elf at its start and removes it after its end. Positions are widened so
+// that an assignment ending at the last section can still be closed.
+fn get_coverage(assignments: &[Assignment]) -> Vec<(Range, usize)> {
+    let mut events: Vec<(u64, i64)> = Vec::with_capacity(assignments.len() * 2);
+    for assignment in assignments {
+        events.push((assignment.sections.start as u64, 1));
+        events.push((assignment.sections.end as u64 + 1, -1));
+    }
+    events.sort();
+
+    let mut coverage = Vec::new();
+    let mut elves: i64 = 0;
+    let mut index = 0;
+    while index < events.len() {
+        let position = events[index].0;
+        while index < events.len() && events[index].0 == position {
+            elves += events[index].1;
+            index += 1;
+        }
+        if elves > 0 && index < events.len() {
+            let run = Range::inclusive(position as u32, (events[index].0 - 1) as u32);
+            coverage.push((run, elves as usize));
+        }
+    }
+    return coverage;
+}
+
+// Sorting by start, then by end with the widest first, puts every container before what it
+// contains. Earlier assignments are kept in order of their ends, so the ones still reaching past
+// each assignment's end can be listed without comparing every pair.
+fn get_containments(assignments: &[Assignment]) -> Vec<(Assignment, Assignment)> {
+    let mut sorted: Vec<&Assignment> = assignments.iter().collect();
+    sorted.sort_by(|a, b| {
+        a.sections
+            .start
+            .cmp(&b.sections.start)
+            .then(b.sections.end.cmp(&a.sections.end))
+            .then(a.line.cmp(&b.line))
+            .then(a.elf.cmp(&b.elf))
+    });
+
+    let mut containments = Vec::new();
+    let mut by_end: BTreeMap<u32, Vec<&Assignment>> = BTreeMap::new();
+    for assignment in sorted {
+        for containers in by_end.range(assignment.sections.end..).map(|(_, containers)| containers) {
+            for container in containers {
+                containments.push((**container, *assignment));
+            }
+        }
+        by_end.entry(assignment.sections.end).or_default().push(assignment);
+    }
+    containments.sort_by_key(|(outer, inner)| (outer.line, outer.elf, inner.line, inner.elf));
+    return containments;
+}
+
+pub fn get_sweep_analysis(custom_input: Option<String>, policy: ReversedRangePolicy) -> Result<SweepAnalysis, String> {
+    let input = get_input(custom_input);
+    let assignments = match get_assignments(&input, policy) {
+        Ok(assignments) => assignments,
+        Err(error) => return Err(error),
+    };
+
+    let coverage = get_coverage(&assignments);
+    let max_coverage = coverage.iter().map(|(_, elves)| *elves).max().unwrap_or(0);
+    let max_coverage_sections = IntervalSet::from_intervals(
+        coverage
+            .iter()
+            .filter(|(_, elves)| *elves == max_coverage)
+            .map(|(sections, _)| *sections),
+    );
+
+    return Ok(SweepAnalysis {
+        assignments: assignments.len(),
+        max_coverage,
+        max_coverage_sections,
+        coverage,
+        containments: get_containments(&assignments),
+    });
+}
+
+pub fn get_sweep_analysis_tables(custom_input: Option<String>, policy: ReversedRangePolicy) -> Result<Vec<Table>, String> {
+    let analysis = match get_sweep_analysis(custom_input, policy) {
+        Ok(result) => result,
+        Err(error) => return Err(error),
+    };
+
+    let mut summary = Table::new(&[("Statistic", Alignment::Left), ("Value", Alignment::Right)]);
+    summary.add_row(vec!["Assignments".to_string(), analysis.assignments.to_string()]);
+    summary.add_row(vec!["Most elves on one section".to_string(), analysis.max_coverage.to_string()]);
+    summary.add_row(vec!["Sections with the most elves".to_string(), analysis.max_coverage_sections.to_string()]);
+    summary.add_row(vec!["Containing pairs".to_string(), analysis.containments.len().to_string()]);
+
+    let mut coverage = Table::new(&[("Sections", Alignment::Left), ("Elves", Alignment::Right)]);
+    for (sections, elves) in &analysis.coverage {
+        coverage.add_row(vec![sections.to_string(), elves.to_string()]);
+    }
+
+    let mut containments = Table::new(&[
+        ("Outer", Alignment::Right),
+        ("Outer sections", Alignment::Left),
+        ("Inner", Alignment::Right),
+        ("Inner sections", Alignment::Left),
+    ]);
+    for (outer, inner) in &analysis.containments {
+        containments.add_row(vec![
+            outer.label(),
+            outer.sections.to_string(),
+            inner.label(),
+            inner.sections.to_string(),
+        ]);
+    }
+
+    return Ok(vec![summary, coverage, containments]);
+}
+
+#[derive(Debug, Clone)]
+pub struct CoverageReport {
+    pub span: Range,
+    pub covered: IntervalSet<u32>,
+    pub gaps: IntervalSet<u32>,
+    // Assignments whose sections are all covered by other assignments. Two identical assignments
+    // are both redundant, though only one of them can be dropped.
+    pub redundant: Vec<Assignment>,
+    pub cover: Vec<Assignment>,
+}
+
+// Takes assignments in order of their start, and from each group that could continue the covered
+// sections keeps the one that reaches furthest. This gives a smallest possible cover.
+fn get_greedy_cover(assignments: &[Assignment]) -> Vec<Assignment> {
+    let mut sorted: Vec<&Assignment> = assignments.iter().collect();
+    sorted.sort_by_key(|assignment| (assignment.sections.start, assignment.line, assignment.elf));
+
+    let mut cover = Vec::new();
+    let mut next_uncovered: Option<u64> = None;
+    let mut index = 0;
+    while index < sorted.len() {
+        let target = match next_uncovered {
+            Some(section) => section.max(sorted[index].sections.start as u64),
+            None => sorted[index].sections.start as u64,
+        };
+        let mut best = sorted[index];
+        while index < sorted.len() && sorted[index].sections.start as u64 <= target {
+            if sorted[index].sections.end > best.sections.end {
+                best = sorted[index];
+            }
+            index += 1;
+        }
+        if best.sections.end as u64 >= target {
+            cover.push(*best);
+            next_uncovered = Some(best.sections.end as u64 + 1);
+        }
+    }
+    return cover;
+}
+
+pub fn get_coverage_report(custom_input: Option<String>, policy: ReversedRangePolicy) -> Result<CoverageReport, String> {
+    let input = get_input(custom_input);
+    let assignments = match get_assignments(&input, policy) {
+        Ok(assignments) => assignments,
+        Err(error) => return Err(error),
+    };
+
+    let covered = IntervalSet::from_intervals(assignments.iter().map(|assignment| assignment.sections));
+    let span = match (covered.intervals().first(), covered.intervals().last()) {
+        (Some(first), Some(last)) => Range::inclusive(first.start, last.end),
+        _ => Range::empty(),
+    };
+
+    // an assignment is needed only if it has a section no other assignment covers
+    let single_coverage = IntervalSet::from_intervals(
+        get_coverage(&assignments)
+            .into_iter()
+            .filter(|(_, elves)| *elves == 1)
+            .map(|(sections, _)| sections),
+    );
+    let redundant = assignments
+        .iter()
+        .filter(|assignment| single_coverage.intersection(&IntervalSet::from_intervals([assignment.sections])).is_empty())
+        .copied()
+        .collect();
+
+    return Ok(CoverageReport {
+        span,
+        gaps: covered.complement(span),
+        covered,
+        redundant,
+        cover: get_greedy_cover(&assignments),
+    });
+}
+
+pub fn get_coverage_report_tables(custom_input: Option<String>, policy: ReversedRangePolicy) -> Result<Vec<Table>, String> {
+    let report = match get_coverage_report(custom_input, policy) {
+        Ok(result) => result,
+        Err(error) => return Err(error),
+    };
+
+    let mut summary = Table::new(&[("Statistic", Alignment::Left), ("Value", Alignment::Right)]);
+    summary.add_row(vec!["Span".to_string(), report.span.to_string()]);
+    summary.add_row(vec!["Covered sections".to_string(), report.covered.len().to_string()]);
+    summary.add_row(vec!["Uncovered sections".to_string(), report.gaps.len().to_string()]);
+    summary.add_row(vec!["Redundant assignments".to_string(), report.redundant.len().to_string()]);
+    summary.add_row(vec!["Assignments in cover".to_string(), report.cover.len().to_string()]);
+
+    let mut gaps = Table::new(&[("Uncovered", Alignment::Left), ("Sections", Alignment::Right)]);
+    for gap in report.gaps.intervals() {
+        gaps.add_row(vec![gap.to_string(), gap.len().to_string()]);
+    }
+
+    let mut redundant = Table::new(&[("Redundant", Alignment::Right), ("Sections", Alignment::Left)]);
+    for assignment in &report.redundant {
+        redundant.add_row(vec![assignment.label(), assignment.sections.to_string()]);
+    }
+
+    let mut cover = Table::new(&[("Cover", Alignment::Right), ("Sections", Alignment::Left)]);
+    for assignment in &report.cover {
+        cover.add_row(vec![assignment.label(), assignment.sections.to_string()]);
+    }
+
+    return Ok(vec![summary, gaps, redundant, cover]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_range_neither_contains_nor_overlaps() {
+        let input = Some("9-3,1-1".to_string());
+        assert_eq!(solve_part_1_with_policy(input.clone(), ReversedRangePolicy::Empty), Ok("0".to_string()));
+        assert_eq!(solve_part_2_with_policy(input, ReversedRangePolicy::Empty), Ok("0".to_string()));
+    }
+
+    #[test]
+    fn normalized_range_contains_and_overlaps() {
+        let input = Some("9-3,4-5".to_string());
+        assert_eq!(solve_part_1_with_policy(input.clone(), ReversedRangePolicy::Normalize), Ok("1".to_string()));
+        assert_eq!(solve_part_2_with_policy(input, ReversedRangePolicy::Normalize), Ok("1".to_string()));
+    }
+}
@@ -1,5 +1,9 @@
+use crate::stats;
+
 pub static EXAMPLE_INPUT: &str = include_str!("../example_input/day5.txt");
 
+pub static SOLVER_VERSION: u32 = 4;
+
 fn get_input(custom_input: Option<String>) -> String {
     return match custom_input {
         Some(custom_input) => custom_input,
@@ -14,13 +18,6 @@ struct Step {
     source: usize,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum InputState {
-    Stacks,
-    StackLabels,
-    Steps,
-}
-
 fn parse_step(line: &str) -> Result<Step, String> {
     let parts: Vec<&str> = line.split(' ').collect();
     if parts.len() != 6 {
@@ -35,76 +32,282 @@ fn parse_step(line: &str) -> Result<Step, String> {
         return Err(format!("Can't parse destination in step '{}'", line));
     }
     let amount = parts[1].parse::<usize>().unwrap();
-    let source = parts[3].parse::<usize>().unwrap() - 1;
-    let destination = parts[5].parse::<usize>().unwrap() - 1;
+    let source_label = parts[3].parse::<usize>().unwrap();
+    let destination_label = parts[5].parse::<usize>().unwrap();
+
+    if source_label == 0 {
+        return Err(format!("Invalid source in step '{}'", line));
+    } else if destination_label == 0 {
+        return Err(format!("Invalid destination in step '{}'", line));
+    }
+    let source = source_label - 1;
+    let destination = destination_label - 1;
 
     return Ok(Step { amount, destination, source })
 }
 
-fn parse_input(input: String) -> Result<(Vec<Vec<char>>, Vec<Step>), String> {
-    let mut state = InputState::Stacks;
-    let mut stack_lines = Vec::new();
-    let mut steps = Vec::new();
-    let mut num_stacks: usize = 0;
-    for line in input.lines() {
-        match state {
-            InputState::Stacks => {
-                if line.get(0..3).is_some() && line.get(0..3).unwrap() == " 1 " {
-                    state = InputState::StackLabels;
-                    num_stacks = line.trim().split(' ').count();
-                } else {
-                    stack_lines.push(line.to_string());
-                }
+pub type Stack = Vec<String>;
+
+// How far each line of a drawing reaches, trailing spaces included, so that a drawing is redrawn
+// with the padding it was read with. Lines shorter than their crates are never cut short.
+#[derive(Debug, Clone, Default)]
+pub struct Padding {
+    // Widths in characters, starting from the label line and going up.
+    line_widths: Vec<usize>,
+    // Whether lines above the original drawing are padded to the full width, which they are if
+    // every line of the original drawing was.
+    pad_new_lines: bool,
+}
+
+impl Padding {
+    // Every line is trimmed.
+    pub fn none() -> Padding {
+        return Padding::default();
+    }
+
+    fn from_drawing(lines: &[&str]) -> Padding {
+        let line_widths: Vec<usize> = lines.iter().rev().map(|line| line.chars().count()).collect();
+        let is_padded = lines.last().is_some_and(|line| line.ends_with(' '))
+            && line_widths.iter().all(|width| *width == line_widths[0]);
+        return Padding { line_widths, pad_new_lines: is_padded };
+    }
+}
+
+struct ParsedInput {
+    stacks: Vec<Stack>,
+    steps: Vec<Step>,
+    padding: Padding,
+}
+
+// Finds the columns each stack label spans, as [start, end) character columns. Labels must count
+// up from 1.
+fn parse_stack_labels(line: &str, line_number: usize) -> Result<Vec<(usize, usize)>, String> {
+    let characters: Vec<char> = line.chars().collect();
+    let mut label_columns = Vec::new();
+    let mut column = 0;
+    while column < characters.len() {
+        if characters[column].is_whitespace() {
+            column += 1;
+            continue;
+        }
+        let start = column;
+        while column < characters.len() && !characters[column].is_whitespace() {
+            column += 1;
+        }
+        let label: String = characters[start..column].iter().collect();
+        let expected_label = label_columns.len() + 1;
+        if label != expected_label.to_string() {
+            return Err(format!(
+                "Expected stack label {} at line {}, column {}: '{}'",
+                expected_label,
+                line_number,
+                start + 1,
+                label
+            ));
+        }
+        label_columns.push((start, column));
+    }
+
+    if label_columns.is_empty() {
+        return Err(format!("Missing stack labels at line {}", line_number));
+    }
+    return Ok(label_columns);
+}
+
+// Reads the crates on one line of the drawing, with the column each starts at. A crate belongs to
+// the stack whose label shares a column with the crate's label.
+fn parse_crate_row(line: &str, line_number: usize, label_columns: &[(usize, usize)]) -> Result<Vec<Option<(String, usize)>>, String> {
+    let characters: Vec<char> = line.chars().collect();
+    let mut row = vec![None; label_columns.len()];
+    let mut column = 0;
+    while column < characters.len() {
+        if characters[column].is_whitespace() {
+            column += 1;
+            continue;
+        } else if characters[column] != '[' {
+            return Err(format!(
+                "Unexpected character at line {}, column {}: '{}'",
+                line_number,
+                column + 1,
+                characters[column]
+            ));
+        }
+
+        let open = column;
+        let close = match characters[open + 1..].iter().position(|character| *character == ']') {
+            Some(offset) => open + 1 + offset,
+            None => return Err(format!("Unclosed crate at line {}, column {}", line_number, open + 1)),
+        };
+        let drawn_crate: String = characters[open..=close].iter().collect();
+        let label: String = characters[open + 1..close].iter().collect();
+        if label.is_empty() || label.chars().any(|character| character.is_whitespace() || character == '[') {
+            return Err(format!("Invalid crate at line {}, column {}: '{}'", line_number, open + 1, drawn_crate));
+        }
+
+        let aligned_stacks: Vec<usize> = label_columns
+            .iter()
+            .enumerate()
+            .filter(|(_, (start, end))| *start < close && *end > open + 1)
+            .map(|(stack, _)| stack)
+            .collect();
+        match aligned_stacks.as_slice() {
+            [stack] if row[*stack].is_none() => row[*stack] = Some((label, open + 1)),
+            [stack] => {
+                return Err(format!(
+                    "Second crate for stack {} at line {}, column {}: '{}'",
+                    stack + 1,
+                    line_number,
+                    open + 1,
+                    drawn_crate
+                ));
+            }
+            [] => {
+                return Err(format!(
+                    "Crate doesn't align with any stack at line {}, column {}: '{}'",
+                    line_number,
+                    open + 1,
+                    drawn_crate
+                ));
             }
-            InputState::StackLabels => {
-                state = InputState::Steps
+            _ => {
+                return Err(format!(
+                    "Crate spans more than one stack at line {}, column {}: '{}'",
+                    line_number,
+                    open + 1,
+                    drawn_crate
+                ));
             }
-            InputState::Steps => {
-                let step_result = parse_step(line);
-                if step_result.is_err() {
-                    return Err(step_result.err().unwrap());
+        }
+        column = close + 1;
+    }
+    return Ok(row);
+}
+
+// The drawing runs up to the first blank line, and its last line labels the stacks. Steps follow
+// the blank line.
+fn parse_input(input: String) -> Result<ParsedInput, String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let drawing_length = match lines.iter().position(|line| line.trim().is_empty()) {
+        Some(length) if length > 0 => length,
+        _ => return Err("Incomplete input".to_string()),
+    };
+
+    let label_line = lines[drawing_length - 1];
+    let label_columns = match parse_stack_labels(label_line, drawing_length) {
+        Ok(columns) => columns,
+        Err(error) => return Err(error),
+    };
+    let num_stacks = label_columns.len();
+
+    let mut stacks: Vec<Stack> = vec![vec![]; num_stacks];
+    for (index, line) in lines[..drawing_length - 1].iter().enumerate().rev() {
+        let row = match parse_crate_row(line, index + 1, &label_columns) {
+            Ok(row) => row,
+            Err(error) => return Err(error),
+        };
+        let level = drawing_length - 2 - index;
+        for (stack_index, drawn_crate) in row.into_iter().enumerate() {
+            if let Some((label, column)) = drawn_crate {
+                if stacks[stack_index].len() != level {
+                    return Err(format!("Crate has nothing under it at line {}, column {}: '{}'", index + 1, column, label));
                 }
-                steps.push(step_result.unwrap());
+                stacks[stack_index].push(label);
             }
         }
     }
 
-    if state != InputState::Steps {
-        return Err("Incomplete input".to_string());
+    let mut steps = Vec::new();
+    for line in &lines[drawing_length + 1..] {
+        let step_result = parse_step(line);
+        if step_result.is_err() {
+            return Err(step_result.err().unwrap());
+        }
+        steps.push(step_result.unwrap());
     }
 
-    let mut stacks = vec![vec![]; num_stacks];
-    for line in stack_lines.iter().rev() {
-        for stack_index in 0..num_stacks {
-            let crate_index = (4 * stack_index) + 1;
-            let crate_label_result = line.chars().nth(crate_index);
-            if crate_label_result.is_none() {
-                break;
-            }
-            let crate_label = crate_label_result.unwrap();
-            if crate_label == ' ' {
-                continue;
-            } else {
-                stacks[stack_index].push(crate_label);
-            }
+    for step in &steps {
+        if step.source >= num_stacks || step.destination >= num_stacks {
+            return Err(format!("Stack missing for step '{:?}'", step));
         }
     }
 
-    return Ok((stacks, steps));
+    return Ok(ParsedInput { stacks, steps, padding: Padding::from_drawing(&lines[..drawing_length]) });
 }
 
-fn get_top_crates(stacks: Vec<Vec<char>>) -> String {
-    let mut top_crates = String::new();
+// Single-character crates are joined as the puzzle expects. If any top crate is wider, they are
+// separated by spaces, since joining 'AB' and 'C' would read the same as 'A' and 'BC'.
+fn get_top_crates(stacks: Vec<Stack>) -> String {
+    let mut top_crates = Vec::new();
     for mut stack in stacks {
         let top_crate_result = stack.pop();
         if top_crate_result.is_some() {
             top_crates.push(top_crate_result.unwrap())
         }
     }
-    return top_crates;
+    if top_crates.iter().all(|top_crate| top_crate.chars().count() == 1) {
+        return top_crates.concat();
+    }
+    return top_crates.join(" ");
 }
 
-pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
+// How a crane moves crates. Each step is carried out as a series of lifts, each taking crates off
+// the top of the source stack and putting them on the destination stack in the same order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crane {
+    // Lifts one crate at a time, so a step reverses the crates it moves.
+    CrateMover9000,
+    // Lifts all of a step's crates at once, keeping their order.
+    CrateMover9001,
+    // Lifts up to this many crates at a time.
+    Capacity(usize),
+}
+
+impl Crane {
+    pub fn parse(name: &str) -> Result<Crane, String> {
+        return match name {
+            "9000" => Ok(Crane::CrateMover9000),
+            "9001" => Ok(Crane::CrateMover9001),
+            _ => match name.strip_prefix("capacity:").map(|capacity| capacity.parse::<usize>()) {
+                Some(Ok(capacity)) if capacity > 0 => Ok(Crane::Capacity(capacity)),
+                _ => Err(format!("Unrecognized crane: '{}'", name)),
+            },
+        };
+    }
+
+    pub fn name(&self) -> String {
+        return match self {
+            Crane::CrateMover9000 => "9000".to_string(),
+            Crane::CrateMover9001 => "9001".to_string(),
+            Crane::Capacity(capacity) => format!("capacity:{}", capacity),
+        };
+    }
+
+    fn get_lift_size(&self, remaining: usize) -> usize {
+        return match self {
+            Crane::CrateMover9000 => 1,
+            Crane::CrateMover9001 => remaining,
+            Crane::Capacity(capacity) => remaining.min(*capacity),
+        };
+    }
+}
+
+fn apply_step(stacks: &mut [Stack], step: &Step, crane: Crane) -> Result<(), String> {
+    if stacks[step.source].len() < step.amount {
+        return Err(format!("Crate missing for step '{:?}'", step));
+    }
+
+    let mut remaining = step.amount;
+    while remaining > 0 {
+        let lift_size = crane.get_lift_size(remaining).max(1);
+        let lift_start = stacks[step.source].len() - lift_size;
+        let lifted = stacks[step.source].split_off(lift_start);
+        stacks[step.destination].extend(lifted);
+        remaining -= lift_size;
+    }
+    return Ok(());
+}
+
+pub fn solve_with_crane(custom_input: Option<String>, crane: Crane) -> Result<String, String> {
     let input = get_input(custom_input);
 
     let parse_result = parse_input(input);
@@ -112,15 +315,12 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
         return Err(parse_result.err().unwrap());
     }
 
-    let (mut stacks, steps) = parse_result.unwrap();
+    let ParsedInput { mut stacks, steps, .. } = parse_result.unwrap();
+    stats::mark_parsed();
     for step in steps {
-        for _ in 0..step.amount {
-            let crate_to_move_result = stacks[step.source].pop();
-            if crate_to_move_result.is_none() {
-                return Err(format!("Crate missing for step '{:?}'", step));
-            }
-            let crate_to_move = crate_to_move_result.unwrap();
-            stacks[step.destination].push(crate_to_move);
+        let step_result = apply_step(&mut stacks, &step, crane);
+        if step_result.is_err() {
+            return Err(step_result.err().unwrap());
         }
     }
 
@@ -128,31 +328,229 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
     return Ok(top_crates)
 }
 
+pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
+    return solve_with_crane(custom_input, Crane::CrateMover9000);
+}
+
 pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
+    return solve_with_crane(custom_input, Crane::CrateMover9001);
+}
+
+// Draws stacks the way the puzzle input does, with the tallest stack's top crate on the first line
+// and the stack labels on the last. Each stack's column is wide enough for its label and its
+// widest crate, with both centred in it, so drawings in the puzzle's layout are reproduced exactly
+// when drawn with the padding they were parsed with.
+pub fn render_stacks(stacks: &[Stack], padding: &Padding) -> String {
+    return render_stacks_styled(stacks, padding, |_, _, cell| cell.to_string());
+}
+
+// Like `render_stacks`, passing each crate and label through `style` along with its stack index and
+// its level in the stack, where the label row has no level. Padding between cells isn't styled.
+pub fn render_stacks_styled<F>(stacks: &[Stack], padding: &Padding, style: F) -> String
+where
+    F: Fn(usize, Option<usize>, &str) -> String,
+{
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = stacks
+        .iter()
+        .enumerate()
+        .map(|(index, stack)| {
+            let label_width = (index + 1).to_string().len() + 2;
+            let crate_width = stack.iter().map(|label| label.chars().count() + 2).max().unwrap_or(0);
+            label_width.max(crate_width)
+        })
+        .collect();
+
+    // each row has a cell per stack, holding the stack's level (None for labels) and text, or
+    // None where the stack has no crate
+    type Cell = Option<(Option<usize>, String)>;
+    let mut rows: Vec<Vec<Cell>> = Vec::new();
+    for level in (0..height).rev() {
+        rows.push(
+            stacks
+                .iter()
+                .zip(widths.iter())
+                .map(|(stack, width)| {
+                    stack
+                        .get(level)
+                        .map(|crate_label| (Some(level), format!("{:^width$}", format!("[{}]", crate_label), width = width)))
+                })
+                .collect(),
+        );
+    }
+    rows.push(
+        widths
+            .iter()
+            .enumerate()
+            .map(|(index, width)| Some((None, format!("{:^width$}", index + 1, width = width))))
+            .collect(),
+    );
+
+    let full_width = widths.iter().sum::<usize>() + widths.len().saturating_sub(1);
+    let mut lines = Vec::new();
+    for (index, mut row) in rows.into_iter().enumerate() {
+        // rows are trimmed before styling, so that styles don't stop trailing space being trimmed,
+        // then padded back out with plain spaces
+        while let Some(None) = row.last() {
+            row.pop();
+        }
+        if let Some(Some((_, text))) = row.last_mut() {
+            *text = text.trim_end().to_string();
+        }
+        let cell_widths = row.iter().enumerate().map(|(stack, cell)| match cell {
+            Some((_, text)) => text.chars().count(),
+            None => widths[stack],
+        });
+        let content_width = cell_widths.sum::<usize>() + row.len().saturating_sub(1);
+        let line_width = match padding.line_widths.get(height - index) {
+            Some(width) => *width,
+            None if padding.pad_new_lines => full_width,
+            None => 0,
+        };
+
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(stack, cell)| match cell {
+                Some((level, text)) => style(stack, *level, text),
+                None => " ".repeat(widths[stack]),
+            })
+            .collect();
+        let mut line = cells.join(" ");
+        line.push_str(" ".repeat(line_width.saturating_sub(content_width)).as_str());
+        lines.push(line);
+    }
+    return lines.join("\n");
+}
+
+// Step 0 is the drawing before any crates have moved, and without a step the final state is drawn.
+pub fn render_state(custom_input: Option<String>, crane: Crane, after_step: Option<usize>) -> Result<String, String> {
     let input = get_input(custom_input);
 
     let parse_result = parse_input(input);
     if parse_result.is_err() {
         return Err(parse_result.err().unwrap());
     }
+    let ParsedInput { mut stacks, steps, padding } = parse_result.unwrap();
 
-    let (mut stacks, steps) = parse_result.unwrap();
-    for step in steps {
-        let mut crates_to_move = Vec::new();
-        for _ in 0..step.amount {
-            let crate_to_move_result = stacks[step.source].pop();
-            if crate_to_move_result.is_none() {
-                return Err(format!("Crate missing for step '{:?}'", step));
-            }
-            let crate_to_move = crate_to_move_result.unwrap();
-            crates_to_move.push(crate_to_move);
+    let step_count = match after_step {
+        Some(step_count) if step_count > steps.len() => {
+            return Err(format!("Step not found: '{}', there are only {} steps", step_count, steps.len()));
         }
-        crates_to_move.reverse();
-        for crate_label in crates_to_move {
-            stacks[step.destination].push(crate_label);
+        Some(step_count) => step_count,
+        None => steps.len(),
+    };
+    for step in steps.iter().take(step_count) {
+        let step_result = apply_step(&mut stacks, step, crane);
+        if step_result.is_err() {
+            return Err(step_result.err().unwrap());
         }
     }
 
-    let top_crates = get_top_crates(stacks);
-    return Ok(top_crates)
+    return Ok(render_stacks(&stacks, &padding));
+}
+
+#[derive(Debug, Clone)]
+pub struct Frame {
+    // 0 for the starting drawing, otherwise the number of steps taken.
+    pub step: usize,
+    pub description: String,
+    pub stacks: Vec<Stack>,
+    // The stacks used by the step, and how many crates ended up on the destination.
+    pub source: Option<usize>,
+    pub destination: Option<usize>,
+    pub moved: usize,
+}
+
+// The starting drawing followed by the state after every step. Also returns the padding of the
+// input's drawing, so that frames can be drawn the same way.
+pub fn get_frames(custom_input: Option<String>, crane: Crane) -> Result<(Vec<Frame>, Padding), String> {
+    let input = get_input(custom_input);
+
+    let parse_result = parse_input(input);
+    if parse_result.is_err() {
+        return Err(parse_result.err().unwrap());
+    }
+    let ParsedInput { mut stacks, steps, padding } = parse_result.unwrap();
+
+    let mut frames = vec![Frame {
+        step: 0,
+        description: "start".to_string(),
+        stacks: stacks.clone(),
+        source: None,
+        destination: None,
+        moved: 0,
+    }];
+    for (index, step) in steps.iter().enumerate() {
+        let step_result = apply_step(&mut stacks, step, crane);
+        if step_result.is_err() {
+            return Err(step_result.err().unwrap());
+        }
+        frames.push(Frame {
+            step: index + 1,
+            description: format!("move {} from {} to {}", step.amount, step.source + 1, step.destination + 1),
+            stacks: stacks.clone(),
+            source: Some(step.source),
+            destination: Some(step.destination),
+            moved: step.amount,
+        });
+    }
+
+    return Ok((frames, padding));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(drawing: &str) {
+        let input = format!("{}\n\nmove 1 from 1 to 2\n", drawing);
+        let parsed = parse_input(input).unwrap();
+        assert_eq!(render_stacks(&parsed.stacks, &parsed.padding), drawing);
+    }
+
+    #[test]
+    fn round_trip_padded() {
+        assert_round_trip("    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ");
+    }
+
+    #[test]
+    fn round_trip_unpadded() {
+        assert_round_trip("    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3");
+    }
+
+    #[test]
+    fn round_trip_ragged() {
+        assert_round_trip("    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3 ");
+        assert_round_trip("    [D]  \n[N] [C]\n[Z] [M] [P]\n 1   2   3");
+    }
+
+    #[test]
+    fn round_trip_ten_or_more_stacks() {
+        assert_round_trip(
+            "[A]             [J]\n[B] [C]     [E] [K] [L]                  [X]\n 1   2   3   4   5   6   7   8   9   10   11 ",
+        );
+    }
+
+    #[test]
+    fn round_trip_wide_crates() {
+        assert_round_trip("    [AB]\n[N] [CD]\n 1   2  ");
+        assert_round_trip("    [AB]    \n[N] [CD] [E]\n 1   2    3 ");
+        assert_round_trip("[AB]     [DEF]\n[CD] [E]  [G]\n 1    2    3");
+    }
+
+    #[test]
+    fn top_crates_are_separated_when_wide() {
+        let stacks = vec![vec!["A".to_string()], vec!["B".to_string()]];
+        assert_eq!(get_top_crates(stacks), "AB");
+        let stacks = vec![vec!["AB".to_string()], vec!["C".to_string()]];
+        assert_eq!(get_top_crates(stacks), "AB C");
+    }
+
+    #[test]
+    fn render_after_moves_keeps_line_padding() {
+        let input = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\n";
+        let rendered = render_state(Some(input.to_string()), Crane::CrateMover9000, None).unwrap();
+        assert_eq!(rendered, "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3 ");
+    }
 }
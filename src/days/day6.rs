@@ -1,6 +1,9 @@
+use crate::stats;
 
 pub static EXAMPLE_INPUT: &str = include_str!("../example_input/day6.txt");
 
+pub static SOLVER_VERSION: u32 = 1;
+
 fn get_input(custom_input: Option<String>) -> String {
     return match custom_input {
         Some(custom_input) => custom_input,
@@ -8,22 +11,20 @@ fn get_input(custom_input: Option<String>) -> String {
     };
 }
 
-fn find_distinct_characters_index(input: String, characters: usize) -> Result<usize, String> {
-    if input.len() < characters {
+fn find_distinct_characters_index(input_characters: &[char], characters: usize) -> Result<usize, String> {
+    if input_characters.len() < characters {
         return Err("Input too short".to_string());
     }
 
     let mut marker_index = None;
-    let num_windows = input.len() - (characters - 1);
-    for window_start_index in 0..num_windows {
-        let window = &input[window_start_index..(window_start_index + characters)];
-        let mut seen = String::new();
+    for (window_start_index, window) in input_characters.windows(characters).enumerate() {
+        let mut seen = Vec::new();
 
-        for char in window.chars() {
+        for char in window {
             if seen.contains(char) {
                 break;
             }
-            seen.push(char);
+            seen.push(*char);
         }
         if seen.len() == characters {
             marker_index = Some(window_start_index + characters);
@@ -39,9 +40,10 @@ fn find_distinct_characters_index(input: String, characters: usize) -> Result<us
 }
 
 pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
-    let input = get_input(custom_input);
+    let input_characters: Vec<char> = get_input(custom_input).chars().collect();
+    stats::mark_parsed();
 
-    let marker_result = find_distinct_characters_index(input, 4);
+    let marker_result = find_distinct_characters_index(&input_characters, 4);
     if marker_result.is_err() {
         return Err(marker_result.err().unwrap());
     } else {
@@ -50,9 +52,10 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
 }
 
 pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
-    let input = get_input(custom_input);
+    let input_characters: Vec<char> = get_input(custom_input).chars().collect();
+    stats::mark_parsed();
 
-    let marker_result = find_distinct_characters_index(input, 14);
+    let marker_result = find_distinct_characters_index(&input_characters, 14);
     if marker_result.is_err() {
         return Err(marker_result.err().unwrap());
     } else {
@@ -1,7 +1,11 @@
 use std::collections::HashMap;
 
+use crate::stats;
+
 pub static EXAMPLE_INPUT: &str = include_str!("../example_input/day7.txt");
 
+pub static SOLVER_VERSION: u32 = 1;
+
 fn get_input(custom_input: Option<String>) -> String {
     return match custom_input {
         Some(custom_input) => custom_input,
@@ -9,6 +13,9 @@ fn get_input(custom_input: Option<String>) -> String {
     };
 }
 
+type DirectoryContents = HashMap<String, Vec<String>>;
+type FileSizes = HashMap<String, u64>;
+
 enum Destination {
     Directory(String),
     Root,
@@ -41,19 +48,17 @@ fn parse_command(command: &str) -> Result<Command, String> {
     };
 }
 
-fn get_working_directory_path(working_directory: &Vec<String>) -> String {
+fn get_working_directory_path(working_directory: &[String]) -> String {
     let mut working_directory_path = "/".to_string();
     working_directory_path.push_str(working_directory.join("/").as_str());
     return working_directory_path;
 }
 
-fn parse_input(
-    input: String,
-) -> Result<(HashMap<String, Vec<String>>, HashMap<String, u64>), String> {
+fn parse_input(input: String) -> Result<(DirectoryContents, FileSizes), String> {
     let mut working_directory = vec![];
     let mut list_output_expected = false;
-    let mut directory_contents: HashMap<String, Vec<String>> = HashMap::new();
-    let mut file_sizes: HashMap<String, u64> = HashMap::new();
+    let mut directory_contents: DirectoryContents = HashMap::new();
+    let mut file_sizes: FileSizes = HashMap::new();
 
     for line in input.lines() {
         if line.starts_with("$ ") {
@@ -77,7 +82,7 @@ fn parse_input(
                         working_directory.clear();
                     }
                     Destination::Up => {
-                        if working_directory.len() > 0 {
+                        if !working_directory.is_empty() {
                             working_directory.pop();
                         } else {
                             return Err(format!(
@@ -93,7 +98,7 @@ fn parse_input(
                 let working_directory_path = get_working_directory_path(&working_directory);
 
                 let directory_name = &line[4..line.len()];
-                if directory_name.len() == 0
+                if directory_name.is_empty()
                     || directory_name
                         .matches(char::is_alphanumeric)
                         .collect::<String>()
@@ -103,8 +108,8 @@ fn parse_input(
                     return Err(format!("Invalid directory name in ls entry: '{}'", line));
                 }
                 let mut directory_path = working_directory_path.clone();
-                if !directory_path.ends_with("/") {
-                    directory_path.push_str("/");
+                if !directory_path.ends_with('/') {
+                    directory_path.push('/');
                 }
                 directory_path.push_str(directory_name);
 
@@ -126,8 +131,8 @@ fn parse_input(
 
                 let working_directory_path = get_working_directory_path(&working_directory);
                 let mut file_path = working_directory_path.clone();
-                if !file_path.ends_with("/") {
-                    file_path.push_str("/");
+                if !file_path.ends_with('/') {
+                    file_path.push('/');
                 }
                 file_path.push_str(name);
 
@@ -136,15 +141,12 @@ fn parse_input(
                     .and_modify(|contents| contents.push(file_path.clone()))
                     .or_insert(vec![file_path.clone()]);
 
-                if file_sizes.contains_key(&file_path) {
-                    if file_sizes[&file_path] != size {
-                        return Err(format!(
-                            "Duplicate file entry found with wrong size: '{}', original size: '{}'",
-                            line, file_sizes[&file_path]
-                        ));
-                    }
-                } else {
-                    file_sizes.insert(file_path, size);
+                let original_size = *file_sizes.entry(file_path).or_insert(size);
+                if original_size != size {
+                    return Err(format!(
+                        "Duplicate file entry found with wrong size: '{}', original size: '{}'",
+                        line, original_size
+                    ));
                 }
             }
         } else {
@@ -155,7 +157,7 @@ fn parse_input(
     return Ok((directory_contents, file_sizes));
 }
 
-fn get_directory_sizes(directory_contents: HashMap<String, Vec<String>>, file_sizes: HashMap<String, u64>) -> Result<HashMap<String, u64>, String> {
+fn get_directory_sizes(directory_contents: DirectoryContents, file_sizes: FileSizes) -> Result<HashMap<String, u64>, String> {
     let mut directory_sizes: HashMap<String, u64> = HashMap::new();
     let mut directories: Vec<&String> = directory_contents.keys().collect();
     directories.sort_by_cached_key(|name| name.len());
@@ -164,15 +166,19 @@ fn get_directory_sizes(directory_contents: HashMap<String, Vec<String>>, file_si
     for directory in directories {
         let mut size: u64 = 0;
         for entry in &directory_contents[directory] {
-            if file_sizes.contains_key(entry) {
-                size += file_sizes[entry];
+            let entry_size = if file_sizes.contains_key(entry) {
+                file_sizes[entry]
             } else if directory_sizes.contains_key(entry) {
-                size += directory_sizes[entry];
+                directory_sizes[entry]
             } else {
                 return Err(format!(
                     "Unable to find entry '{}' for directory '{}'",
                     entry, directory
                 ));
+            };
+            match size.checked_add(entry_size) {
+                Some(total) => size = total,
+                None => return Err(format!("Directory size too large: '{}'", directory)),
             }
         }
         directory_sizes.insert(directory.clone(), size);
@@ -188,6 +194,7 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
         return Err(parse_result.err().unwrap());
     }
     let (directory_contents, file_sizes) = parse_result.unwrap();
+    stats::mark_parsed();
 
     let size_results = get_directory_sizes(directory_contents, file_sizes);
     if size_results.is_err() {
@@ -195,7 +202,7 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
     }
     let directory_sizes = size_results.unwrap();
 
-    let mut sizes: Vec<u64> = directory_sizes.into_iter().map(|(_, v)| v).collect();
+    let mut sizes: Vec<u64> = directory_sizes.into_values().collect();
     sizes.retain(|size| *size <= 100_000);
     let sum: u64 = sizes.iter().sum();
 
@@ -210,6 +217,7 @@ pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
         return Err(parse_result.err().unwrap());
     }
     let (directory_contents, file_sizes) = parse_result.unwrap();
+    stats::mark_parsed();
 
     let size_results = get_directory_sizes(directory_contents, file_sizes);
     if size_results.is_err() {
@@ -219,7 +227,15 @@ pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
 
     let total_filesystem_size: u64 = 70_000_000;
     let required_unused_space: u64 = 30_000_000;
-    let current_unused_space = total_filesystem_size - directory_sizes["/"];
+    let root_size_result = directory_sizes.get("/");
+    if root_size_result.is_none() {
+        return Err("Root directory size unknown".to_string());
+    }
+    let root_size = *root_size_result.unwrap();
+    if root_size > total_filesystem_size {
+        return Err(format!("Root directory larger than filesystem: '{}'", root_size));
+    }
+    let current_unused_space = total_filesystem_size - root_size;
 
     if current_unused_space >= required_unused_space {
         return Err("Already enough unused space present".to_string());
@@ -227,11 +243,11 @@ pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
 
     let additional_required_space = required_unused_space - current_unused_space;
 
-    let mut sizes: Vec<u64> = directory_sizes.into_iter().map(|(_, v)| v).collect();
+    let mut sizes: Vec<u64> = directory_sizes.into_values().collect();
     sizes.retain(|size| *size >= additional_required_space);
     sizes.sort();
 
-    if sizes.len() == 0 {
+    if sizes.is_empty() {
         return Err("No directories found that were large enough to fee up required space".to_string());
     }
 
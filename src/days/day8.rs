@@ -1,7 +1,13 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::stats;
+
 pub static EXAMPLE_INPUT: &str = include_str!("../example_input/day8.txt");
 
+pub static SOLVER_VERSION: u32 = 1;
+
+type TreeHeights = HashMap<(u32, u32), u8>;
+
 fn get_input(custom_input: Option<String>) -> String {
     return match custom_input {
         Some(custom_input) => custom_input,
@@ -9,8 +15,8 @@ fn get_input(custom_input: Option<String>) -> String {
     };
 }
 
-fn parse_tree_heights(input: String) -> Result<(u32, u32, HashMap<(u32, u32), u8>), String> {
-    let mut tree_heights: HashMap<(u32, u32), u8> = HashMap::new();
+fn parse_tree_heights(input: String) -> Result<(u32, u32, TreeHeights), String> {
+    let mut tree_heights: TreeHeights = HashMap::new();
     let mut width: Option<u32> = None;
     let mut height: u32 = 0;
     for line in input.lines() {
@@ -20,15 +26,12 @@ fn parse_tree_heights(input: String) -> Result<(u32, u32, HashMap<(u32, u32), u8
             return Err(format!("Line length invalid, expected '{}', got '{}'", width.unwrap(), line.len()));
         }
 
-        let mut index: u32 = 0;
-        for tree_entry in line.chars() {
+        for (index, tree_entry) in (0_u32..).zip(line.chars()) {
             if !tree_entry.is_ascii_digit() {
                 return Err(format!("Invalid tree entry: '{}'", tree_entry));
             }
             let tree_height: u8 = tree_entry.to_digit(10).unwrap() as u8;
             tree_heights.insert((index, height), tree_height);
-
-            index += 1;
         }
         height += 1;
     }
@@ -48,6 +51,7 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
         return Err(parse_results.err().unwrap());
     }
     let (width, height, tree_heights) = parse_results.unwrap();
+    stats::mark_parsed();
     let mut visible_trees: HashSet<(u32, u32)> = HashSet::new();
 
     for x in 0..width {
@@ -55,10 +59,10 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
 
         // scan from top to bottom
         for y in 0..height {
-            let current_tree_height = tree_heights.get(&(x, y)).unwrap().clone();
+            let current_tree_height = *tree_heights.get(&(x, y)).unwrap();
             if tallest_so_far.is_none() || tallest_so_far.unwrap() < current_tree_height {
                 visible_trees.insert((x, y));
-                tallest_so_far = Some(current_tree_height.clone());
+                tallest_so_far = Some(current_tree_height);
             }
             if tallest_so_far.is_some() && tallest_so_far.unwrap() == 9 {
                 // early exit
@@ -70,10 +74,10 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
 
         // scan from bottom to top
         for y in (0..height).rev() {
-            let current_tree_height = tree_heights.get(&(x, y)).unwrap().clone();
+            let current_tree_height = *tree_heights.get(&(x, y)).unwrap();
             if tallest_so_far.is_none() || tallest_so_far.unwrap() < current_tree_height {
                 visible_trees.insert((x, y));
-                tallest_so_far = Some(current_tree_height.clone());
+                tallest_so_far = Some(current_tree_height);
             }
             if tallest_so_far.is_some() && tallest_so_far.unwrap() == 9 {
                 // early exit
@@ -87,10 +91,10 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
 
         // scan from left to right
         for x in 0..width {
-            let current_tree_height = tree_heights.get(&(x, y)).unwrap().clone();
+            let current_tree_height = *tree_heights.get(&(x, y)).unwrap();
             if tallest_so_far.is_none() || tallest_so_far.unwrap() < current_tree_height {
                 visible_trees.insert((x, y));
-                tallest_so_far = Some(current_tree_height.clone());
+                tallest_so_far = Some(current_tree_height);
             }
             if tallest_so_far.is_some() && tallest_so_far.unwrap() == 9 {
                 // early exit
@@ -102,10 +106,10 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
 
         // scan from right to left
         for x in (0..width).rev() {
-            let current_tree_height = tree_heights.get(&(x, y)).unwrap().clone();
+            let current_tree_height = *tree_heights.get(&(x, y)).unwrap();
             if tallest_so_far.is_none() || tallest_so_far.unwrap() < current_tree_height {
                 visible_trees.insert((x, y));
-                tallest_so_far = Some(current_tree_height.clone());
+                tallest_so_far = Some(current_tree_height);
             }
             if tallest_so_far.is_some() && tallest_so_far.unwrap() == 9 {
                 // early exit
@@ -117,13 +121,13 @@ pub fn solve_part_1(custom_input: Option<String>) -> Result<String, String> {
     return Ok(format!("{}", visible_trees.len()));
 }
 
-fn get_scenic_score(tree_heights: &HashMap<(u32, u32), u8>, (width, height): (u32, u32), (tree_x, tree_y): (u32, u32)) -> u64 {
-    let tree_height = tree_heights.get(&(tree_x, tree_y)).unwrap().clone();
+fn get_scenic_score(tree_heights: &TreeHeights, (width, height): (u32, u32), (tree_x, tree_y): (u32, u32)) -> u64 {
+    let tree_height = *tree_heights.get(&(tree_x, tree_y)).unwrap();
 
     let mut right_viewing_distance = 0;
     for x in (tree_x + 1)..width {
         right_viewing_distance += 1;
-        let current_tree_height = tree_heights.get(&(x, tree_y)).unwrap().clone();
+        let current_tree_height = *tree_heights.get(&(x, tree_y)).unwrap();
         if current_tree_height >= tree_height {
             break;
         }
@@ -132,7 +136,7 @@ fn get_scenic_score(tree_heights: &HashMap<(u32, u32), u8>, (width, height): (u3
     let mut left_viewing_distance = 0;
     for x in (0..tree_x).rev() {
         left_viewing_distance += 1;
-        let current_tree_height = tree_heights.get(&(x, tree_y)).unwrap().clone();
+        let current_tree_height = *tree_heights.get(&(x, tree_y)).unwrap();
         if current_tree_height >= tree_height {
             break;
         }
@@ -141,7 +145,7 @@ fn get_scenic_score(tree_heights: &HashMap<(u32, u32), u8>, (width, height): (u3
     let mut up_viewing_distance = 0;
     for y in (0..tree_y).rev() {
         up_viewing_distance += 1;
-        let current_tree_height = tree_heights.get(&(tree_x, y)).unwrap().clone();
+        let current_tree_height = *tree_heights.get(&(tree_x, y)).unwrap();
         if current_tree_height >= tree_height {
             break;
         }
@@ -150,7 +154,7 @@ fn get_scenic_score(tree_heights: &HashMap<(u32, u32), u8>, (width, height): (u3
     let mut down_viewing_distance = 0;
     for y in (tree_y + 1)..height {
         down_viewing_distance += 1;
-        let current_tree_height = tree_heights.get(&(tree_x, y)).unwrap().clone();
+        let current_tree_height = *tree_heights.get(&(tree_x, y)).unwrap();
         if current_tree_height >= tree_height {
             break;
         }
@@ -167,6 +171,7 @@ pub fn solve_part_2(custom_input: Option<String>) -> Result<String, String> {
         return Err(parse_results.err().unwrap());
     }
     let (width, height, tree_heights) = parse_results.unwrap();
+    stats::mark_parsed();
     if width <= 2 || height <= 2 {
         return Ok("0".to_string());
     }
@@ -1,6 +1,9 @@
 
 pub static EXAMPLE_INPUT: &str = include_str!("../example_input/day1.txt");
 
+// Bump whenever a change to this solver could change its answers, so cached answers are discarded.
+pub static SOLVER_VERSION: u32 = 1;
+
 fn get_input(custom_input: Option<String>) -> String {
     return match custom_input {
         Some(custom_input) => custom_input,
@@ -0,0 +1,372 @@
+use std::fmt;
+
+// Integer types that can bound an interval. i128 and u128 aren't covered, since lengths are
+// counted in u128 and the full range of either wouldn't fit.
+pub trait Integer: Copy + Ord + fmt::Debug + fmt::Display {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn checked_increment(self) -> Option<Self>;
+    fn checked_decrement(self) -> Option<Self>;
+
+    // Distance from the type's minimum, which keeps lengths exact for every type.
+    fn offset(self) -> u128;
+}
+
+macro_rules! impl_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl Integer for $integer {
+                const MIN: Self = <$integer>::MIN;
+                const MAX: Self = <$integer>::MAX;
+
+                fn checked_increment(self) -> Option<Self> {
+                    return self.checked_add(1);
+                }
+
+                fn checked_decrement(self) -> Option<Self> {
+                    return self.checked_sub(1);
+                }
+
+                fn offset(self) -> u128 {
+                    return (self as i128).wrapping_sub(<$integer>::MIN as i128) as u128;
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+// An inclusive range of integers. Any interval whose start is after its end is empty, and half-open
+// ranges are converted to inclusive ones when they are created. Constructors and operations give
+// every empty interval the same bounds as `Interval::empty()`, so empty intervals compare equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    pub fn inclusive(start: T, end: T) -> Interval<T> {
+        if start > end {
+            return Interval::empty();
+        }
+        return Interval { start, end };
+    }
+
+    pub fn half_open(start: T, end: T) -> Interval<T> {
+        return match end.checked_decrement() {
+            Some(inclusive_end) => Interval::inclusive(start, inclusive_end),
+            None => Interval::empty(),
+        };
+    }
+
+    pub fn empty() -> Interval<T> {
+        return Interval { start: T::MAX, end: T::MIN };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.start > self.end;
+    }
+
+    pub fn len(&self) -> u128 {
+        if self.is_empty() {
+            return 0;
+        }
+        return self.end.offset() - self.start.offset() + 1;
+    }
+
+    // The exclusive end is None if the interval runs to the type's maximum.
+    pub fn to_half_open(&self) -> Option<(T, T)> {
+        if self.is_empty() {
+            return Some((self.start, self.start));
+        }
+        return self.end.checked_increment().map(|end| (self.start, end));
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        return self.start <= value && value <= self.end;
+    }
+
+    pub fn contains_interval(&self, interval: &Interval<T>) -> bool {
+        return interval.is_empty() || (self.start <= interval.start && self.end >= interval.end);
+    }
+
+    pub fn overlaps(&self, interval: &Interval<T>) -> bool {
+        return !self.intersection(interval).is_empty();
+    }
+
+    pub fn intersection(&self, interval: &Interval<T>) -> Interval<T> {
+        if self.is_empty() || interval.is_empty() {
+            return Interval::empty();
+        }
+        return Interval::inclusive(self.start.max(interval.start), self.end.min(interval.end));
+    }
+
+    // True if the intervals overlap or sit next to each other, so their union is one interval.
+    fn touches(&self, interval: &Interval<T>) -> bool {
+        let (first, second) = if self.start <= interval.start { (self, interval) } else { (interval, self) };
+        return match first.end.checked_increment() {
+            Some(after_end) => second.start <= after_end,
+            None => true,
+        };
+    }
+}
+
+impl<T: Integer> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "empty");
+        }
+        return write!(f, "{}-{}", self.start, self.end);
+    }
+}
+
+// A set of integers stored as sorted, non-empty intervals that neither overlap nor touch, so each
+// set has exactly one representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> Default for IntervalSet<T> {
+    fn default() -> IntervalSet<T> {
+        return IntervalSet::new();
+    }
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        return IntervalSet { intervals: vec![] };
+    }
+
+    pub fn from_intervals<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> IntervalSet<T> {
+        let mut sorted: Vec<Interval<T>> = intervals.into_iter().filter(|interval| !interval.is_empty()).collect();
+        sorted.sort_by_key(|interval| interval.start);
+
+        let mut normalized: Vec<Interval<T>> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match normalized.last_mut() {
+                Some(last) if last.touches(&interval) => last.end = last.end.max(interval.end),
+                _ => normalized.push(interval),
+            }
+        }
+        return IntervalSet { intervals: normalized };
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        return &self.intervals;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.intervals.is_empty();
+    }
+
+    // The number of integers in the set.
+    pub fn len(&self) -> u128 {
+        return self.intervals.iter().map(|interval| interval.len()).sum();
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = IntervalSet::from_intervals(intervals);
+    }
+
+    fn find(&self, value: T) -> Option<&Interval<T>> {
+        let index = self.intervals.partition_point(|interval| interval.end < value);
+        return self.intervals.get(index).filter(|interval| interval.contains(value));
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        return self.find(value).is_some();
+    }
+
+    pub fn contains_interval(&self, interval: &Interval<T>) -> bool {
+        if interval.is_empty() {
+            return true;
+        }
+        return match self.find(interval.start) {
+            Some(containing) => containing.contains_interval(interval),
+            None => false,
+        };
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        return IntervalSet::from_intervals(self.intervals.iter().chain(other.intervals.iter()).copied());
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let (mut left, mut right) = (0, 0);
+        while left < self.intervals.len() && right < other.intervals.len() {
+            let (a, b) = (&self.intervals[left], &other.intervals[right]);
+            let overlap = a.intersection(b);
+            if !overlap.is_empty() {
+                intervals.push(overlap);
+            }
+            if a.end < b.end {
+                left += 1;
+            } else {
+                right += 1;
+            }
+        }
+        return IntervalSet { intervals };
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        return self.intersection(&other.complement(Interval::inclusive(T::MIN, T::MAX)));
+    }
+
+    // Every integer within `bounds` that isn't in the set.
+    pub fn complement(&self, bounds: Interval<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        if bounds.is_empty() {
+            return IntervalSet { intervals };
+        }
+
+        let mut next_start = Some(bounds.start);
+        for interval in self.intervals.iter().map(|interval| interval.intersection(&bounds)) {
+            if interval.is_empty() {
+                continue;
+            }
+            if let Some(start) = next_start {
+                if start < interval.start {
+                    intervals.push(Interval::inclusive(start, interval.start.checked_decrement().unwrap()));
+                }
+            }
+            next_start = interval.end.checked_increment();
+        }
+        if let Some(start) = next_start {
+            if start <= bounds.end {
+                intervals.push(Interval::inclusive(start, bounds.end));
+            }
+        }
+        return IntervalSet { intervals };
+    }
+}
+
+impl<T: Integer> fmt::Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let intervals: Vec<String> = self.intervals.iter().map(|interval| interval.to_string()).collect();
+        return write!(f, "{}", intervals.join(","));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_counts_from_type_minimum() {
+        assert_eq!(i8::MIN.offset(), 0);
+        assert_eq!((-1i8).offset(), 127);
+        assert_eq!(i8::MAX.offset(), 255);
+        assert_eq!(u8::MAX.offset(), 255);
+        assert_eq!(i64::MAX.offset(), u64::MAX as u128);
+    }
+
+    #[test]
+    fn len_covers_whole_type() {
+        assert_eq!(Interval::inclusive(i8::MIN, i8::MAX).len(), 256);
+        assert_eq!(Interval::inclusive(u64::MIN, u64::MAX).len(), u64::MAX as u128 + 1);
+        assert_eq!(Interval::inclusive(-3i32, 2).len(), 6);
+        assert_eq!(Interval::<u8>::empty().len(), 0);
+    }
+
+    #[test]
+    fn empty_intervals_are_equal() {
+        let first = Interval::inclusive(1u8, 3).intersection(&Interval::inclusive(5, 9));
+        let second = Interval::inclusive(20u8, 30).intersection(&Interval::inclusive(0, 10));
+        assert!(first.is_empty());
+        assert_eq!(first, second);
+        assert_eq!(first, Interval::empty());
+        assert_eq!(Interval::inclusive(3i8, -3), Interval::empty());
+        assert_eq!(Interval::half_open(5i8, 5), Interval::empty());
+    }
+
+    #[test]
+    fn half_open_at_type_minimum_is_empty() {
+        assert!(Interval::half_open(u8::MIN, u8::MIN).is_empty());
+        assert!(Interval::half_open(i8::MIN, i8::MIN).is_empty());
+        assert_eq!(Interval::half_open(i8::MIN, i8::MIN + 2), Interval::inclusive(-128, -127));
+    }
+
+    #[test]
+    fn to_half_open_at_type_maximum_is_none() {
+        assert_eq!(Interval::inclusive(250u8, u8::MAX).to_half_open(), None);
+        assert_eq!(Interval::inclusive(-2i8, 3).to_half_open(), Some((-2, 4)));
+    }
+
+    #[test]
+    fn touches_at_type_maximum() {
+        let upper = Interval::inclusive(200u8, u8::MAX);
+        assert!(upper.touches(&Interval::inclusive(100, 199)));
+        assert!(!upper.touches(&Interval::inclusive(100, 198)));
+        assert!(Interval::inclusive(100u8, 199).touches(&upper));
+        assert!(Interval::inclusive(i8::MAX, i8::MAX).touches(&Interval::inclusive(0, i8::MAX)));
+    }
+
+    #[test]
+    fn from_intervals_merges_touching_intervals() {
+        let set = IntervalSet::from_intervals([
+            Interval::inclusive(10u8, u8::MAX),
+            Interval::inclusive(0, 4),
+            Interval::inclusive(5, 9),
+        ]);
+        assert_eq!(set.intervals(), &[Interval::inclusive(0, u8::MAX)]);
+
+        let set = IntervalSet::from_intervals([Interval::inclusive(-5i8, -1), Interval::inclusive(1, 3), Interval::empty()]);
+        assert_eq!(set.intervals(), &[Interval::inclusive(-5, -1), Interval::inclusive(1, 3)]);
+    }
+
+    #[test]
+    fn complement_stays_within_bounds() {
+        let set = IntervalSet::from_intervals([Interval::inclusive(-100i8, -50), Interval::inclusive(0, 10)]);
+        let complement = set.complement(Interval::inclusive(-60, 20));
+        assert_eq!(complement.intervals(), &[Interval::inclusive(-49, -1), Interval::inclusive(11, 20)]);
+
+        assert!(set.complement(Interval::empty()).is_empty());
+        assert_eq!(
+            IntervalSet::<u8>::new().complement(Interval::inclusive(3, 7)).intervals(),
+            &[Interval::inclusive(3, 7)]
+        );
+    }
+
+    #[test]
+    fn complement_over_whole_type() {
+        let set = IntervalSet::from_intervals([Interval::inclusive(u8::MIN, 9), Interval::inclusive(250, u8::MAX)]);
+        assert_eq!(
+            set.complement(Interval::inclusive(u8::MIN, u8::MAX)).intervals(),
+            &[Interval::inclusive(10, 249)]
+        );
+
+        let full = IntervalSet::from_intervals([Interval::inclusive(i8::MIN, i8::MAX)]);
+        assert!(full.complement(Interval::inclusive(i8::MIN, i8::MAX)).is_empty());
+    }
+
+    #[test]
+    fn difference_at_type_edges() {
+        let full = IntervalSet::from_intervals([Interval::inclusive(i8::MIN, i8::MAX)]);
+        let edges = IntervalSet::from_intervals([Interval::inclusive(i8::MIN, -120), Interval::inclusive(120, i8::MAX)]);
+        let difference = full.difference(&edges);
+        assert_eq!(difference.intervals(), &[Interval::inclusive(-119, 119)]);
+        assert_eq!(difference.len(), 239);
+
+        let upper = IntervalSet::from_intervals([Interval::inclusive(0u32, u32::MAX)]);
+        let lower = IntervalSet::from_intervals([Interval::inclusive(0u32, 99)]);
+        assert_eq!(upper.difference(&lower).intervals(), &[Interval::inclusive(100, u32::MAX)]);
+        assert!(lower.difference(&upper).is_empty());
+    }
+
+    #[test]
+    fn contains_interval_across_sets() {
+        let set = IntervalSet::from_intervals([Interval::inclusive(-10i16, 10), Interval::inclusive(20, i16::MAX)]);
+        assert!(set.contains(i16::MAX));
+        assert!(!set.contains(15));
+        assert!(set.contains_interval(&Interval::inclusive(-10, 10)));
+        assert!(!set.contains_interval(&Interval::inclusive(5, 25)));
+        assert!(set.contains_interval(&Interval::empty()));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod bench;
+pub mod cache;
+pub mod config;
+pub mod days;
+pub mod interval;
+pub mod report;
+pub mod stats;
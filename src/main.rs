@@ -1,59 +1,608 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
-mod days;
+use std::io::{BufReader, IsTerminal, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use aoc::bench;
+use aoc::cache;
+use aoc::config::{self, Config};
+use aoc::days::{self, Part, SolveOptions};
+use aoc::report::{self, Table};
+use aoc::stats;
 
-fn run_day(day: u8, part: Part, input_path: Option<String>) {
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(true);
+
+fn paint_red(text: &str) -> String {
+    if COLOR_ENABLED.load(Ordering::Relaxed) {
+        return format!("\x1B[31m{}\x1B[0m", text);
+    }
+    return text.to_string();
+}
+
+fn paint_green(text: &str) -> String {
+    if COLOR_ENABLED.load(Ordering::Relaxed) {
+        return format!("\x1B[32m{}\x1B[0m", text);
+    }
+    return text.to_string();
+}
+
+fn print_allocation_stats(phase: &str, phase_stats: &stats::AllocationStats) {
+    println!(
+        "{}: {} allocations, {} bytes allocated, {} peak live bytes",
+        phase, phase_stats.allocations, phase_stats.bytes_allocated, phase_stats.peak_live_bytes
+    );
+}
+
+fn load_input(input_path: Option<String>) -> Option<String> {
     let input: Option<String>;
     match input_path {
         Some(path) => {
-            let result = fs::read_to_string(path);
+            let result = fs::read_to_string(&path);
             match result {
                 Ok(contents) => input = Some(contents),
                 Err(_) => {
-                    print_error_and_exit("Failed to load input");
+                    print_error_and_exit(format!("Failed to load input: '{}'", path).as_str());
                     input = None;
                 }
             }
         }
         None => input = None,
     }
-    let result = match day {
-        1 => match part {
-            Part::Part1 => days::day1::solve_part_1(input),
-            Part::Part2 => days::day1::solve_part_2(input),
-        },
-        2 => match part {
-            Part::Part1 => days::day2::solve_part_1(input),
-            Part::Part2 => days::day2::solve_part_2(input),
-        },
-        3 => match part {
-            Part::Part1 => days::day3::solve_part_1(input),
-            Part::Part2 => days::day3::solve_part_2(input),
-        },
-        4 => match part {
-            Part::Part1 => days::day4::solve_part_1(input),
-            Part::Part2 => days::day4::solve_part_2(input),
-        },
-        5 => match part {
-            Part::Part1 => days::day5::solve_part_1(input),
-            Part::Part2 => days::day5::solve_part_2(input),
+    return input;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Plain,
+    Json,
+    Table,
+    Csv,
+}
+
+struct RunSettings {
+    show_stats: bool,
+    cache_path: Option<String>,
+    format: OutputFormat,
+    jobs: usize,
+    solve_options: SolveOptions,
+}
+
+fn print_answer(day: u8, part: Part, answer: &str, format: OutputFormat, label_part: bool) {
+    match format {
+        OutputFormat::Plain | OutputFormat::Table if label_part => println!("Part {}: {}", part.number(), answer),
+        OutputFormat::Plain | OutputFormat::Table => println!("{}", answer),
+        OutputFormat::Csv => println!("{},{},{}", day, part.number(), answer),
+        OutputFormat::Json => println!(
+            "{{\"day\":{},\"part\":{},\"answer\":\"{}\"}}",
+            day,
+            part.number(),
+            report::escape_json(answer)
+        ),
+    }
+}
+
+fn solve_part(day: u8, part: Part, input: Option<String>, cache_path: Option<&str>, options: &SolveOptions) -> Result<String, String> {
+    let path = match cache_path {
+        Some(path) => path,
+        None => return days::solve_with_options(day, part, input, options),
+    };
+    return match cache::solve_with_cache(path, day, part, input, options) {
+        Ok(cached) => {
+            if let Some(error) = cached.save_error {
+                print_warning(error.as_str());
+            }
+            Ok(cached.answer)
+        }
+        Err(error) => Err(error),
+    };
+}
+
+// Rulesets are named built-ins, or otherwise a path to a ruleset definition.
+fn load_rules(rules: &str) -> Result<days::day2::Ruleset, String> {
+    if days::day2::BUILTIN_RULESETS.iter().any(|(name, _)| *name == rules) {
+        return days::day2::get_builtin_ruleset(rules);
+    }
+    return match fs::read_to_string(rules) {
+        Ok(definition) => match days::day2::parse_ruleset(&definition) {
+            Ok(ruleset) => Ok(ruleset),
+            Err(error) => Err(format!("{} (in {})", error, rules)),
         },
-        6 => match part {
-            Part::Part1 => days::day6::solve_part_1(input),
-            Part::Part2 => days::day6::solve_part_2(input),
+        Err(_) => Err(format!("Failed to load rules: '{}'", rules)),
+    };
+}
+
+fn get_day_2_rules(options: &HashMap<CliOption, String>) -> days::day2::Ruleset {
+    return match get_solve_options(2, options).rules {
+        Some(rules) => rules,
+        None => days::day2::get_default_ruleset(),
+    };
+}
+
+fn parse_count(option: CliOption, value: &str) -> usize {
+    return match value.parse::<usize>() {
+        Ok(count) if count > 0 => count,
+        _ => {
+            print_error_and_exit(format!("Invalid {}: '{}'", option.name().to_lowercase(), value).as_str());
+            0
+        }
+    };
+}
+
+fn get_solve_options(day: u8, options: &HashMap<CliOption, String>) -> SolveOptions {
+    let mut solve_options = SolveOptions::default();
+    for option in [CliOption::Compartments, CliOption::GroupSize] {
+        if let Some(value) = options.get(&option) {
+            if day != 3 {
+                print_error_and_exit(format!("{} is only supported for day 3", option.name()).as_str());
+            }
+            match option {
+                CliOption::Compartments => solve_options.compartments = Some(parse_count(option, value)),
+                _ => solve_options.group_size = Some(parse_count(option, value)),
+            }
+        }
+    }
+    if let Some(rules) = options.get(&CliOption::Rules) {
+        if day != 2 {
+            print_error_and_exit("Rules are only supported for day 2");
+        }
+        match load_rules(rules) {
+            Ok(ruleset) => solve_options.rules = Some(ruleset),
+            Err(error) => print_error_and_exit(error.as_str()),
+        }
+    }
+    if let Some(policy) = options.get(&CliOption::Reversed) {
+        if day != 4 {
+            print_error_and_exit("Reversed is only supported for day 4");
+        }
+        solve_options.reversed_ranges = Some(match policy.as_str() {
+            "reject" => days::day4::ReversedRangePolicy::Reject,
+            "normalize" => days::day4::ReversedRangePolicy::Normalize,
+            "empty" => days::day4::ReversedRangePolicy::Empty,
+            _ => {
+                print_error_and_exit(format!("Unrecognized reversed range policy: '{}'", policy).as_str());
+                days::day4::ReversedRangePolicy::Reject
+            }
+        });
+    }
+    if let Some(crane) = options.get(&CliOption::Crane) {
+        if day != 5 {
+            print_error_and_exit("Crane is only supported for day 5");
+        }
+        match days::day5::Crane::parse(crane) {
+            Ok(crane) => solve_options.crane = Some(crane),
+            Err(error) => print_error_and_exit(error.as_str()),
+        }
+    }
+    return solve_options;
+}
+
+// Day 1 inputs are streamed from the file, since generated ones can be larger than memory. Their
+// answers aren't cached, because hashing the input would take a second pass over it.
+fn solve_day_1_from_file(part: Part, path: &str) -> Result<String, String> {
+    return match fs::File::open(path) {
+        Ok(file) => days::day1::solve_part_from_reader(BufReader::new(file), part),
+        Err(_) => Err(format!("Failed to load input: '{}'", path)),
+    };
+}
+
+fn run_day(day: u8, parts: Vec<Part>, input_path: Option<String>, settings: &RunSettings) {
+    let streamed_path = if day == 1 { input_path.clone() } else { None };
+    let streamed_path = streamed_path.as_deref();
+    let input = if streamed_path.is_some() { None } else { load_input(input_path) };
+    let label_part = parts.len() > 1;
+    let cache_path = settings.cache_path.as_deref();
+
+    if settings.format == OutputFormat::Csv {
+        println!("day,part,answer");
+    }
+
+    // allocation counters are global, so parts are run one at a time when reporting them
+    if settings.show_stats || settings.jobs <= 1 || parts.len() <= 1 {
+        for part in parts {
+            // cloned before counting starts, so that the copy isn't counted against the solver
+            let part_input = input.clone();
+            if settings.show_stats {
+                stats::start();
+            }
+            let result = match streamed_path {
+                Some(path) => solve_day_1_from_file(part, path),
+                None => solve_part(day, part, part_input, cache_path, &settings.solve_options),
+            };
+
+            let (parse_stats, solve_stats) = stats::finish();
+
+            match result {
+                Ok(answer) => print_answer(day, part, answer.as_str(), settings.format, label_part),
+                Err(error) => print_error_and_exit(error.as_str()),
+            }
+
+            if settings.show_stats {
+                match parse_stats {
+                    Some(parse_stats) => {
+                        print_allocation_stats("Parse", &parse_stats);
+                        print_allocation_stats("Solve", &solve_stats);
+                    }
+                    None => print_allocation_stats("Parse and solve", &solve_stats),
+                }
+            }
+        }
+        return;
+    }
+
+    let mut results = Vec::new();
+    for chunk in parts.chunks(settings.jobs) {
+        thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|part| {
+                    let part_input = input.clone();
+                    let solve_options = &settings.solve_options;
+                    scope.spawn(move || match streamed_path {
+                        Some(path) => (*part, solve_day_1_from_file(*part, path)),
+                        None => (*part, solve_part(day, *part, part_input, cache_path, solve_options)),
+                    })
+                })
+                .collect();
+            for handle in handles {
+                match handle.join() {
+                    Ok(result) => results.push(result),
+                    Err(_) => print_error_and_exit("Solver thread panicked"),
+                }
+            }
+        });
+    }
+
+    for (part, result) in results {
+        match result {
+            Ok(answer) => print_answer(day, part, answer.as_str(), settings.format, label_part),
+            Err(error) => print_error_and_exit(error.as_str()),
+        }
+    }
+}
+
+fn run_day_1_top(count: usize, input_path: Option<String>, format: OutputFormat) {
+    // stream the input file, since generated day 1 inputs can be larger than memory
+    let result = match input_path {
+        Some(path) => match fs::File::open(&path) {
+            Ok(file) => days::day1::solve_top_from_reader(BufReader::new(file), count),
+            Err(_) => Err(format!("Failed to load input: '{}'", path)),
         },
-        7 => match part {
-            Part::Part1 => days::day7::solve_part_1(input),
-            Part::Part2 => days::day7::solve_part_2(input),
+        None => days::day1::solve_top(None, count),
+    };
+    match result {
+        Ok(answer) => match format {
+            OutputFormat::Json => println!("{{\"day\":1,\"top\":{},\"answer\":\"{}\"}}", count, report::escape_json(&answer)),
+            OutputFormat::Csv => println!("day,top,answer\n1,{},{}", count, answer),
+            OutputFormat::Plain | OutputFormat::Table => println!("{}", answer),
         },
-        8 => match part {
-            Part::Part1 => days::day8::solve_part_1(input),
-            Part::Part2 => days::day8::solve_part_2(input),
+        Err(error) => print_error_and_exit(error.as_str()),
+    }
+}
+
+static DEFAULT_FRAME_DELAY_MS: u64 = 250;
+
+// Moved crates and the destination label are green, and the source label is red.
+fn render_frame(frame: &days::day5::Frame, frame_count: usize, padding: &days::day5::Padding, highlight: bool) -> String {
+    let drawing = days::day5::render_stacks_styled(&frame.stacks, padding, |stack, level, cell| {
+        if !highlight {
+            return cell.to_string();
+        }
+        let is_destination = Some(stack) == frame.destination;
+        return match level {
+            None if Some(stack) == frame.source => paint_red(cell),
+            None if is_destination => paint_green(cell),
+            Some(level) if is_destination && level + frame.moved >= frame.stacks[stack].len() => paint_green(cell),
+            _ => cell.to_string(),
+        };
+    });
+    return format!("Step {}/{}: {}\n\n{}\n", frame.step, frame_count - 1, frame.description, drawing);
+}
+
+// Matches the names written for frames, 'frame-' then the step number then '.txt'.
+fn is_frame_file_name(name: &str) -> bool {
+    return match name.strip_prefix("frame-").and_then(|name| name.strip_suffix(".txt")) {
+        Some(step) => !step.is_empty() && step.chars().all(|character| character.is_ascii_digit()),
+        None => false,
+    };
+}
+
+// Only removes files named like frames, so anything else kept in the directory is left alone.
+fn remove_frame_files(frames_dir: &str) -> Result<(), String> {
+    let entries = match fs::read_dir(frames_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Err(format!("Failed to read frames directory: '{}'", frames_dir)),
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_frame_file_name(&name) && fs::remove_file(entry.path()).is_err() {
+            return Err(format!("Failed to remove old frame: '{}'", entry.path().display()));
+        }
+    }
+    return Ok(());
+}
+
+fn run_day_5_animation(input_path: Option<String>, options: &HashMap<CliOption, String>) {
+    let crane = get_solve_options(5, options).crane.unwrap_or(days::day5::Crane::CrateMover9000);
+    let delay = match options.get(&CliOption::Delay) {
+        Some(delay) => match delay.parse::<u64>() {
+            Ok(delay) => delay,
+            Err(_) => {
+                print_error_and_exit(format!("Invalid delay: '{}'", delay).as_str());
+                0
+            }
         },
-        _ => Err(format!("Day not found: '{}'", day)),
+        None => DEFAULT_FRAME_DELAY_MS,
+    };
+    let (frames, padding) = match days::day5::get_frames(load_input(input_path), crane) {
+        Ok(result) => result,
+        Err(error) => {
+            print_error_and_exit(error.as_str());
+            (vec![], days::day5::Padding::none())
+        }
+    };
+
+    if let Some(frames_dir) = options.get(&CliOption::FramesDir) {
+        if fs::create_dir_all(frames_dir).is_err() {
+            print_error_and_exit(format!("Failed to create frames directory: '{}'", frames_dir).as_str());
+        }
+        // frames from an earlier, longer run would otherwise be mixed in with these
+        if let Err(error) = remove_frame_files(frames_dir) {
+            print_error_and_exit(error.as_str());
+        }
+        for frame in &frames {
+            let path = Path::new(frames_dir).join(format!("frame-{:04}.txt", frame.step));
+            if fs::write(&path, render_frame(frame, frames.len(), &padding, false)).is_err() {
+                print_error_and_exit(format!("Failed to write frame: '{}'", path.display()).as_str());
+            }
+        }
+        println!("Wrote {} frames to '{}'", frames.len(), frames_dir);
+        return;
+    }
+
+    // when writing to a file or pipe, print the frames one after another without cursor movement
+    if !std::io::stdout().is_terminal() {
+        for frame in &frames {
+            println!("{}", render_frame(frame, frames.len(), &padding, false));
+        }
+        return;
+    }
+
+    // clear the screen once, then redraw each frame from the top left corner
+    print!("\x1B[2J");
+    for (index, frame) in frames.iter().enumerate() {
+        print!("\x1B[H\x1B[J{}", render_frame(frame, frames.len(), &padding, true));
+        let _ = std::io::stdout().flush();
+        if index + 1 < frames.len() {
+            thread::sleep(Duration::from_millis(delay));
+        }
+    }
+}
+
+fn print_tables(tables: &[Table], format: OutputFormat) {
+    let formatted: Vec<String> = tables
+        .iter()
+        .map(|table| match format {
+            OutputFormat::Plain | OutputFormat::Table => table.to_text(),
+            OutputFormat::Csv => table.to_csv(),
+            OutputFormat::Json => table.to_json(),
+        })
+        .collect();
+    match format {
+        // several tables are written as one JSON array of tables, so the output stays valid JSON
+        OutputFormat::Json if formatted.len() > 1 => println!("[{}]", formatted.join(",")),
+        _ => println!("{}", formatted.join("\n\n")),
+    }
+}
+
+fn parse_percentiles(percentiles: &str) -> Result<Vec<f64>, String> {
+    let mut parsed = Vec::new();
+    for percentile in percentiles.split(',') {
+        match percentile.trim().parse::<f64>() {
+            Ok(value) if (0.0..=100.0).contains(&value) => parsed.push(value),
+            _ => return Err(format!("Invalid percentile: '{}'", percentile)),
+        }
+    }
+    return Ok(parsed);
+}
+
+fn run_mode(day: u8, mode: &str, input_path: Option<String>, options: &HashMap<CliOption, String>, format: OutputFormat) {
+    let input = load_input(input_path);
+    if (day, mode) == (5, "render") {
+        let crane = get_solve_options(day, options).crane.unwrap_or(days::day5::Crane::CrateMover9000);
+        let step = options.get(&CliOption::Step).map(|step| match step.parse::<usize>() {
+            Ok(step) => step,
+            Err(_) => {
+                print_error_and_exit(format!("Invalid step: '{}'", step).as_str());
+                0
+            }
+        });
+        match days::day5::render_state(input, crane, step) {
+            Ok(drawing) => match format {
+                OutputFormat::Json => println!("{{\"day\":5,\"drawing\":\"{}\"}}", report::escape_json(&drawing)),
+                _ => println!("{}", drawing),
+            },
+            Err(error) => print_error_and_exit(error.as_str()),
+        }
+        return;
+    }
+
+    let result = match (day, mode) {
+        (1, "report") => {
+            let order = match options.get(&CliOption::Sort).map(|order| order.as_str()) {
+                None | Some("total") => days::day1::ReportOrder::Total,
+                Some("index") => days::day1::ReportOrder::Index,
+                Some(order) => {
+                    print_error_and_exit(format!("Unrecognized sort order: '{}'", order).as_str());
+                    days::day1::ReportOrder::Total
+                }
+            };
+            days::day1::get_inventory_report_table(input, order).map(|table| vec![table])
+        }
+        (1, "stats") => {
+            let percentiles = match options.get(&CliOption::Percentiles) {
+                Some(percentiles) => match parse_percentiles(percentiles) {
+                    Ok(percentiles) => percentiles,
+                    Err(error) => {
+                        print_error_and_exit(error.as_str());
+                        vec![]
+                    }
+                },
+                None => days::day1::DEFAULT_PERCENTILES.to_vec(),
+            };
+            days::day1::get_calorie_stats_tables(input, &percentiles)
+        }
+        (2, "mappings") => days::day2::get_mapping_scores_table(input, &get_day_2_rules(options)).map(|table| vec![table]),
+        (2, "breakdown") => days::day2::get_round_breakdown_table(input, &get_day_2_rules(options)).map(|table| vec![table]),
+        (2, "opponent") => days::day2::get_opponent_analysis_tables(input, &get_day_2_rules(options)),
+        (3, "report") => {
+            let solve_options = get_solve_options(day, options);
+            days::day3::get_report_tables(
+                input,
+                solve_options.compartments.unwrap_or(days::day3::DEFAULT_COMPARTMENTS),
+                solve_options.group_size.unwrap_or(days::day3::DEFAULT_GROUP_SIZE),
+            )
+        }
+        (3, "plan") => {
+            let compartments = get_solve_options(day, options).compartments;
+            days::day3::get_reorganization_plan_table(input, compartments.unwrap_or(days::day3::DEFAULT_COMPARTMENTS))
+                .map(|table| vec![table])
+        }
+        (4, "sweep") => {
+            let policy = get_solve_options(day, options).reversed_ranges.unwrap_or_default();
+            days::day4::get_sweep_analysis_tables(input, policy)
+        }
+        (4, "coverage") => {
+            let policy = get_solve_options(day, options).reversed_ranges.unwrap_or_default();
+            days::day4::get_coverage_report_tables(input, policy)
+        }
+        _ => Err(format!("Mode not found for day {}: '{}'", day, mode)),
     };
 
     match result {
-        Ok(answer) => println!("{}", answer),
+        Ok(tables) => print_tables(&tables, format),
+        Err(error) => print_error_and_exit(error.as_str()),
+    }
+}
+
+fn format_nanoseconds(nanoseconds: f64) -> String {
+    return match nanoseconds {
+        n if n >= 1_000_000_000.0 => format!("{:.2}s", n / 1_000_000_000.0),
+        n if n >= 1_000_000.0 => format!("{:.2}ms", n / 1_000_000.0),
+        n if n >= 1_000.0 => format!("{:.2}µs", n / 1_000.0),
+        n => format!("{:.0}ns", n),
+    };
+}
+
+fn run_benchmarks(
+    days_to_run: Vec<(u8, Option<String>, SolveOptions)>,
+    parts: Vec<Part>,
+    iterations: usize,
+    history_path: &str,
+    compare: bool,
+) {
+    let history = match bench::load_history(history_path) {
+        Ok((history, skipped)) => {
+            for error in skipped {
+                print_warning(format!("{}, skipping it", error).as_str());
+            }
+            history
+        }
+        Err(error) => {
+            print_error_and_exit(error.as_str());
+            vec![]
+        }
+    };
+
+    let mut regressions = 0;
+    for (day, input_path, solve_options) in days_to_run {
+        let input = load_input(input_path);
+        for part in &parts {
+            let run = match bench::run_benchmark(day, *part, input.clone(), iterations, &solve_options) {
+                Ok(run) => run,
+                Err(error) => {
+                    print_error_and_exit(format!("Day {} part {}: {}", day, part.number(), error).as_str());
+                    continue;
+                }
+            };
+
+            let mut line = format!(
+                "Day {} part {}: {} ± {} (n={})",
+                day,
+                part.number(),
+                format_nanoseconds(run.mean()),
+                format_nanoseconds(run.standard_deviation()),
+                run.timings.len()
+            );
+            if compare {
+                match bench::find_baseline(&history, &run) {
+                    Some(baseline) => {
+                        let comparison = bench::compare(baseline, &run);
+                        let revision = comparison.baseline_revision.unwrap_or_else(|| "unknown".to_string());
+                        if comparison.is_regression {
+                            regressions += 1;
+                            line.push_str(
+                                format!(
+                                    ", {} than baseline ({}): {:+.1}% (t={:.2})",
+                                    paint_red("slower"),
+                                    revision,
+                                    comparison.change * 100.0,
+                                    comparison.t_statistic
+                                )
+                                .as_str(),
+                            );
+                        } else {
+                            line.push_str(
+                                format!(", {:+.1}% vs baseline ({})", comparison.change * 100.0, revision).as_str(),
+                            );
+                        }
+                    }
+                    None => line.push_str(", no baseline"),
+                }
+            }
+            println!("{}", line);
+
+            if let Err(error) = bench::append_history(history_path, &run) {
+                print_error_and_exit(error.as_str());
+            }
+        }
+    }
+
+    if regressions > 0 {
+        print_error_and_exit(format!("{} benchmark(s) got slower", regressions).as_str());
+    }
+}
+
+fn list_cache(cache_path: &str) {
+    let entries = match cache::load_cache(cache_path) {
+        Ok(entries) => entries,
+        Err(error) => {
+            print_error_and_exit(error.as_str());
+            vec![]
+        }
+    };
+    if entries.is_empty() {
+        println!("Answer cache is empty");
+        return;
+    }
+    for entry in entries {
+        let stale = if entry.is_current() { "" } else { " (stale)" };
+        println!(
+            "Day {} part {}, input {:016x}, solver version {}{}: {}",
+            entry.day,
+            entry.part.number(),
+            entry.input_hash,
+            entry.solver_version,
+            stale,
+            entry.answer
+        );
+    }
+}
+
+fn clear_cache(cache_path: &str) {
+    match cache::clear_cache(cache_path) {
+        Ok(count) => println!("Removed {} cached answer(s)", count),
         Err(error) => print_error_and_exit(error.as_str()),
     }
 }
@@ -63,19 +612,148 @@ enum InputValue {
     None,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CliOption {
+    Input,
+    InputDir,
+    Part,
+    Format,
+    Color,
+    Jobs,
+    AnswerFile,
+    Iterations,
+    History,
+    Top,
+    Mode,
+    Sort,
+    Percentiles,
+    Rules,
+    Compartments,
+    GroupSize,
+    Reversed,
+    Crane,
+    Step,
+    Delay,
+    FramesDir,
+}
+
+impl CliOption {
+    fn name(&self) -> &'static str {
+        return match self {
+            CliOption::Input => "Input",
+            CliOption::InputDir => "Input directory",
+            CliOption::Part => "Part",
+            CliOption::Format => "Format",
+            CliOption::Color => "Color",
+            CliOption::Jobs => "Jobs",
+            CliOption::AnswerFile => "Answer file",
+            CliOption::Iterations => "Iterations",
+            CliOption::History => "History",
+            CliOption::Top => "Top",
+            CliOption::Mode => "Mode",
+            CliOption::Sort => "Sort",
+            CliOption::Percentiles => "Percentiles",
+            CliOption::Rules => "Rules",
+            CliOption::Compartments => "Compartments",
+            CliOption::GroupSize => "Group size",
+            CliOption::Reversed => "Reversed",
+            CliOption::Crane => "Crane",
+            CliOption::Step => "Step",
+            CliOption::Delay => "Delay",
+            CliOption::FramesDir => "Frames directory",
+        };
+    }
+
+    // The matching key in the config file, for options that can be set there.
+    fn config_key(&self) -> Option<&'static str> {
+        return match self {
+            CliOption::InputDir => Some("input_dir"),
+            CliOption::Part => Some("part"),
+            CliOption::Format => Some("format"),
+            CliOption::Color => Some("color"),
+            CliOption::Jobs => Some("jobs"),
+            CliOption::AnswerFile => Some("answer_file"),
+            _ => None,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CliFlag {
+    Help,
+    Stats,
+    Compare,
+    NoCache,
+    Animate,
+}
+
+impl CliFlag {
+    fn name(&self) -> &'static str {
+        return match self {
+            CliFlag::Help => "Help",
+            CliFlag::Stats => "Stats",
+            CliFlag::Compare => "Compare",
+            CliFlag::NoCache => "No cache",
+            CliFlag::Animate => "Animate",
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CliCommand {
+    Bench,
+    Cache,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CliSubcommand {
+    List,
+    Clear,
+}
+
 enum CliArgument {
+    Command(CliCommand),
+    Subcommand(CliSubcommand),
     Day(u8),
     InvalidDay(u8),
-    Input(InputValue),
-    Part(InputValue),
-    Help,
+    Option(CliOption, InputValue),
+    Flag(CliFlag),
     Unrecognized,
 }
 
-enum Part {
-    Part1,
-    Part2,
-}
+static OPTIONS: [(&str, &str, CliOption); 22] = [
+    ("-i", "--input", CliOption::Input),
+    ("", "--input-dir", CliOption::InputDir),
+    ("-p", "--part", CliOption::Part),
+    ("", "--format", CliOption::Format),
+    ("", "--color", CliOption::Color),
+    ("-j", "--jobs", CliOption::Jobs),
+    ("", "--answer-file", CliOption::AnswerFile),
+    // the original name of --answer-file, kept so existing scripts keep working
+    ("", "--cache", CliOption::AnswerFile),
+    ("-n", "--iterations", CliOption::Iterations),
+    ("", "--history", CliOption::History),
+    ("", "--top", CliOption::Top),
+    ("-m", "--mode", CliOption::Mode),
+    ("", "--sort", CliOption::Sort),
+    ("", "--percentiles", CliOption::Percentiles),
+    ("", "--rules", CliOption::Rules),
+    ("", "--compartments", CliOption::Compartments),
+    ("", "--group-size", CliOption::GroupSize),
+    ("", "--reversed", CliOption::Reversed),
+    ("", "--crane", CliOption::Crane),
+    ("", "--step", CliOption::Step),
+    ("", "--delay", CliOption::Delay),
+    ("", "--frames-dir", CliOption::FramesDir),
+];
+
+static FLAGS: [(&str, &str, CliFlag); 5] = [
+    ("-h", "--help", CliFlag::Help),
+    ("", "--stats", CliFlag::Stats),
+    ("", "--compare", CliFlag::Compare),
+    ("", "--no-cache", CliFlag::NoCache),
+    ("", "--animate", CliFlag::Animate),
+];
 
 fn is_valid_day(day: u8) -> bool {
     return day > 0 && day < 31;
@@ -88,33 +766,133 @@ fn parse_day(day: u8) -> CliArgument {
     };
 }
 
+fn parse_parts(parts: &str) -> Result<Vec<Part>, String> {
+    return match parts {
+        "1" => Ok(vec![Part::Part1]),
+        "2" => Ok(vec![Part::Part2]),
+        "both" | "1,2" => Ok(vec![Part::Part1, Part::Part2]),
+        _ => Err(format!("Unrecognized part: '{}'", parts)),
+    };
+}
+
+fn parse_format(format: &str) -> Result<OutputFormat, String> {
+    return match format {
+        "plain" => Ok(OutputFormat::Plain),
+        "json" => Ok(OutputFormat::Json),
+        "table" => Ok(OutputFormat::Table),
+        "csv" => Ok(OutputFormat::Csv),
+        _ => Err(format!("Unrecognized format: '{}'", format)),
+    };
+}
+
+fn parse_color(color: &str) -> Result<bool, String> {
+    return match color {
+        "always" => Ok(true),
+        "never" => Ok(false),
+        "auto" => Ok(std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()),
+        _ => Err(format!("Unrecognized color mode: '{}'", color)),
+    };
+}
+
+fn parse_jobs(jobs: &str) -> Result<usize, String> {
+    return match jobs.parse::<usize>() {
+        Ok(jobs) if jobs > 0 => Ok(jobs),
+        _ => Err(format!("Invalid jobs: '{}'", jobs)),
+    };
+}
+
 fn parse_argument(arg: String) -> CliArgument {
-    return match arg.as_str() {
-        "-h" | "--help" => CliArgument::Help,
-        "-i" | "--input" => CliArgument::Input(InputValue::None),
-        i if i.starts_with("-i=") => {
-            CliArgument::Input(InputValue::String(i.trim_start_matches("-i=").to_string()))
+    for (short, long, flag) in FLAGS {
+        if arg == short || arg == long {
+            return CliArgument::Flag(flag);
         }
-        i if i.starts_with("--input=") => CliArgument::Input(InputValue::String(
-            i.trim_start_matches("--input=").to_string(),
-        )),
-        "-p" | "--part" => CliArgument::Part(InputValue::None),
-        p if p.starts_with("-p=") => {
-            CliArgument::Part(InputValue::String(p.trim_start_matches("-p=").to_string()))
+    }
+    for (short, long, option) in OPTIONS {
+        if arg == short || arg == long {
+            return CliArgument::Option(option, InputValue::None);
         }
-        p if p.starts_with("--part=") => {
-            CliArgument::Part(InputValue::String(p.trim_start_matches("--part=").to_string()))
+        for name in [short, long] {
+            let prefix = format!("{}=", name);
+            if !name.is_empty() && arg.starts_with(prefix.as_str()) {
+                return CliArgument::Option(
+                    option,
+                    InputValue::String(arg.trim_start_matches(prefix.as_str()).to_string()),
+                );
+            }
         }
+    }
+    return match arg.as_str() {
+        "bench" => CliArgument::Command(CliCommand::Bench),
+        "cache" => CliArgument::Command(CliCommand::Cache),
+        "list" => CliArgument::Subcommand(CliSubcommand::List),
+        "clear" => CliArgument::Subcommand(CliSubcommand::Clear),
         day if day.parse::<u8>().is_ok() => parse_day(day.parse::<u8>().unwrap()),
         _ => CliArgument::Unrecognized,
     };
 }
 
-static USAGE: &str = "aoc <day> [options]";
+// Looks up a setting, preferring the command line, then the environment, then the config file.
+// Returns the value along with where it came from, for error messages.
+fn get_setting(options: &HashMap<CliOption, String>, config: &Config, option: CliOption) -> Option<(String, String)> {
+    if let Some(value) = options.get(&option) {
+        return Some((value.clone(), "command line".to_string()));
+    }
+    let key = option.config_key()?;
+    let env_var_name = config::get_env_var_name(key);
+    if let Ok(value) = std::env::var(&env_var_name) {
+        return Some((value, env_var_name));
+    }
+    return config
+        .get(key)
+        .map(|config_value| (config_value.value.clone(), config_value.source.clone()));
+}
+
+fn parse_setting<T>(
+    options: &HashMap<CliOption, String>,
+    config: &Config,
+    option: CliOption,
+    parse: fn(&str) -> Result<T, String>,
+) -> Option<T> {
+    let (value, source) = get_setting(options, config, option)?;
+    return match parse(value.as_str()) {
+        Ok(parsed) => Some(parsed),
+        Err(error) if source == "command line" => {
+            print_error_and_exit(error.as_str());
+            None
+        }
+        Err(error) => {
+            print_error_and_exit(format!("{} (from {})", error, source).as_str());
+            None
+        }
+    };
+}
+
+fn get_input_path(day: u8, options: &HashMap<CliOption, String>, config: &Config) -> Option<String> {
+    if let Some(input) = options.get(&CliOption::Input) {
+        return Some(input.clone());
+    }
+    return get_setting(options, config, CliOption::InputDir).map(|(input_dir, _)| {
+        Path::new(&input_dir)
+            .join(format!("day{}.txt", day))
+            .to_string_lossy()
+            .to_string()
+    });
+}
+
+static USAGE: &str = "aoc <day> [options]
+       aoc bench [day] [options]
+       aoc cache <list|clear> [options]";
 static HELP_MESSAGE: &str = "
 Run the Advent of Code 2022 solution for the given day.
 
-If no input is given, the example input will be used.
+If no input is given, the example input will be used. Answers are cached by
+day, part, input and solver version, and are reused until the solver changes.
+
+Commands:
+  bench                Time solutions and record them in the benchmark
+                       history. Runs every day if no day is given.
+  cache list           List cached answers.
+  cache clear          Remove all cached answers.
 
 Arguments:
   day:                 The day to run the solution for.                  [int]
@@ -122,43 +900,142 @@ Arguments:
 Options:
   --help:              Show help text.                               [boolean]
   --input <path>       Specify which input to use.                    [string]
-  --part <1 or 2>      Specify which part of the puzzle to solve.        [int]
+  --input-dir <path>   Directory containing 'day<N>.txt' inputs.      [string]
+  --part <1, 2, both>  Specify which part(s) of the puzzle to solve.  [string]
+  --format <format>    'plain', 'json', 'table' or 'csv'.             [string]
+  --color <when>       Colour output: 'auto', 'always' or 'never'.    [string]
+  --jobs <n>           Number of parts to solve in parallel.             [int]
+  --mode <mode>        Run a day-specific mode instead of a part.     [string]
+  --stats              Report allocations for parse and solve.       [boolean]
+                       Requires the 'alloc-stats' feature.
+  --no-cache           Always run the solver, ignoring the cache.    [boolean]
+  --answer-file <path> Answer cache file. Alias: --cache <path>.      [string]
+                       Default: '.aoc-answer-cache'.
+
+Bench options:
+  --iterations <n>     Number of timed runs per part. Default: 100.      [int]
+  --history <path>     Benchmark history file.                        [string]
+                       Default: '.aoc-bench-history'.
+  --compare            Flag parts that are slower than the median of [boolean]
+                       the last 10 stored runs on the same input.
+
+Day 1 options:
+  Input files are read in a single streaming pass, and their answers are not
+  cached.
+  --top <n>            Sum the calories carried by the top n elves.      [int]
+                       Part 1 is '--top 1' and part 2 is '--top 3'.
+  --mode report        List each elf's items, total and rank.
+  --sort <order>       Report order: 'total' or 'index'.              [string]
+  --mode stats         Summarise the distribution of calorie totals.
+  --percentiles <list> Comma-separated percentiles for stats.         [string]
+                       Default: '25,50,75,90'.
+
+Day 2 options:
+  --mode mappings      Score the guide under every meaning of X, Y and Z.
+  --mode breakdown     Score each round as both parts read it.
+  --mode opponent      Analyse the opponent's moves and compare the guide
+                       with the best possible responses.
+  --rules <rules>      Play a built-in ruleset ('rps' or 'rpsls'),    [string]
+                       or one defined in a file with lines like
+                       'shape = Rock A X 1', 'beats = Rock Scissors'
+                       and 'win = Z 6' (also 'lose' and 'draw').
+
+Day 3 options:
+  --mode report        List the items shared by each rucksack and group.
+  --mode plan          Find the fewest moves between compartments that
+                       leave no item type in more than one. Long rucksacks
+                       split into many compartments are too slow to plan,
+                       and are rejected.
+  --compartments <k>   Split each rucksack into k compartments.          [int]
+                       Default: 2.
+  --group-size <g>     Find badges across groups of g elves.             [int]
+                       Default: 3.
+
+Day 4 options:
+  --mode sweep         Count elves on each section across all lines, and
+                       list every pair of assignments where one contains
+                       the other. Assignments are written 'line:elf'.
+  --mode coverage      List uncovered sections and redundant assignments,
+                       and find a smallest set of assignments covering
+                       the same sections.
+  --reversed <policy>  How to read ranges like '9-3': 'reject',       [string]
+                       'normalize' (read as '3-9') or 'empty' (covers
+                       no sections, so never contains or overlaps).
+                       Default: 'reject'.
+
+Day 5 options:
+  Crates may have labels longer than one character. If a top crate does, the
+  answer separates the top crates with spaces.
+  --crane <crane>      Move crates with this crane in every part:     [string]
+                       '9000' (one at a time, as in part 1), '9001'
+                       (all at once, as in part 2) or 'capacity:<n>'
+                       (up to n at a time).
+  --mode render        Draw the stacks after the last step, using the
+                       crane from '--crane' (default: '9000').
+  --step <n>           Draw the stacks after step n instead. Step 0 is   [int]
+                       the starting drawing.
+  --animate            Play the steps back in the terminal, with the [boolean]
+                       crane from '--crane' (default: '9000').
+  --delay <ms>         Time to show each frame for. Default: 250.        [int]
+  --frames-dir <path>  Write each frame of the animation to a         [string]
+                       numbered file in path instead of playing it.
+                       Frames left by earlier runs are removed.
+
+Configuration:
+  Defaults for input-dir, part, format, color, jobs and answer-file can be set
+  in an 'aoc.toml' file with 'key = value' lines, using underscores in keys
+  (e.g. 'input_dir = inputs'). The nearest 'aoc.toml' in the working directory
+  or its parents is used, on top of '$XDG_CONFIG_HOME/aoc/aoc.toml'. Each key
+  can also be set with an environment variable, e.g. 'AOC_INPUT_DIR'.
+
+  Precedence: command line > environment > config file > built-in default.
 ";
 
+// Warnings go to stderr so they don't mix with answers printed as JSON or CSV.
+fn print_warning(message: &str) {
+    eprintln!("{}: {}", paint_red("Warning"), message);
+}
+
 fn print_error_and_exit(error_message: &str) {
-    println!("\x1B[31mError\x1B[0m: {}", error_message);
+    println!("{}: {}", paint_red("Error"), error_message);
     std::process::exit(1);
 }
 
 fn main() {
-    let mut help: bool = false;
+    let mut command: Option<CliCommand> = None;
+    let mut subcommand: Option<CliSubcommand> = None;
     let mut day: Option<u8> = None;
-    let mut next_arg_is_input = false;
-    let mut chosen_part: Option<Part> = None;
-    let mut next_arg_is_part = false;
-    let mut input: Option<String> = None;
+    let mut flags: HashSet<CliFlag> = HashSet::new();
+    let mut options: HashMap<CliOption, String> = HashMap::new();
+    let mut pending_option: Option<CliOption> = None;
+
+    COLOR_ENABLED.store(parse_color("auto").unwrap_or(false), Ordering::Relaxed);
 
     for arg in std::env::args().skip(1) {
-        let parsed_arg = parse_argument(arg.clone());
-        if next_arg_is_input {
-            next_arg_is_input = false;
-            input = Some(arg);
-            continue;
-        } else if next_arg_is_part {
-            next_arg_is_part = false;
-            match arg.as_str() {
-                "1" => chosen_part = Some(Part::Part1),
-                "2" => chosen_part = Some(Part::Part2),
-                _ => print_error_and_exit(format!("Unrecognized part: '{}'", arg).as_str()),
-            }
+        if let Some(option) = pending_option {
+            pending_option = None;
+            options.insert(option, arg);
             continue;
         }
+        let parsed_arg = parse_argument(arg.clone());
         match parsed_arg {
-            CliArgument::Help => {
-                if help {
-                    print_error_and_exit("Help flag provided twice");
+            CliArgument::Command(parsed_command) => {
+                if command.is_some() || day.is_some() {
+                    print_error_and_exit(format!("Unexpected command: '{}'", arg).as_str());
+                }
+                command = Some(parsed_command);
+            }
+            CliArgument::Subcommand(parsed_subcommand) => {
+                if command != Some(CliCommand::Cache) || subcommand.is_some() {
+                    print_error_and_exit(format!("Unexpected command: '{}'", arg).as_str());
+                }
+                subcommand = Some(parsed_subcommand);
+            }
+            CliArgument::Flag(flag) => {
+                if flags.contains(&flag) {
+                    print_error_and_exit(format!("{} flag provided twice", flag.name()).as_str());
                 }
-                help = true;
+                flags.insert(flag);
             }
             CliArgument::Day(day_number) => {
                 if day.is_some() {
@@ -166,42 +1043,17 @@ fn main() {
                 }
                 day = Some(day_number);
             }
-            CliArgument::Input(input_value) => match input_value {
-                InputValue::String(path) => {
-                    if input.is_some() {
-                        print_error_and_exit("Input provided twice");
-                    }
-                    input = Some(path);
+            CliArgument::Option(option, input_value) => {
+                if options.contains_key(&option) {
+                    print_error_and_exit(format!("{} provided twice", option.name()).as_str());
                 }
-                InputValue::None => {
-                    if next_arg_is_input {
-                        print_error_and_exit("Input flag provided twice");
-                    } else if input.is_some() {
-                        print_error_and_exit("Input provided twice");
+                match input_value {
+                    InputValue::String(value) => {
+                        options.insert(option, value);
                     }
-                    next_arg_is_input = true
+                    InputValue::None => pending_option = Some(option),
                 }
-            },
-            CliArgument::Part(input_value) => match input_value {
-                InputValue::String(part_num) => {
-                    if chosen_part.is_some() {
-                        print_error_and_exit("Part provided twice");
-                    }
-                    match part_num.as_str() {
-                        "1" => chosen_part = Some(Part::Part1),
-                        "2" => chosen_part = Some(Part::Part2),
-                        _ => print_error_and_exit(format!("Unrecognized part: '{}'", part_num).as_str()),
-                    }
-                }
-                InputValue::None => {
-                    if next_arg_is_part {
-                        print_error_and_exit("Part flag provided twice");
-                    } else if chosen_part.is_some() {
-                        print_error_and_exit("Part provided twice");
-                    }
-                    next_arg_is_part = true
-                }
-            },
+            }
             CliArgument::InvalidDay(invalid_day) => {
                 print_error_and_exit(format!("Unrecognized day: '{}'", invalid_day).as_str());
             }
@@ -211,15 +1063,133 @@ fn main() {
         }
     }
 
-    if help {
+    if let Some(option) = pending_option {
+        print_error_and_exit(format!("{} flag provided without a value", option.name()).as_str());
+    }
+
+    if flags.contains(&CliFlag::Help) {
         println!("{}\n{}", USAGE, HELP_MESSAGE);
-    } else if day.is_some() {
-        let part = match chosen_part {
-            Some(p) => p,
-            None => Part::Part1,
-        };
-        run_day(day.unwrap(), part, input);
-    } else {
-        print_error_and_exit("Must specify day to run");
+        return;
+    }
+
+    let config = match config::load_config() {
+        Ok(config) => config,
+        Err(error) => {
+            print_error_and_exit(error.as_str());
+            Config::default()
+        }
+    };
+
+    if let Some(color) = parse_setting(&options, &config, CliOption::Color, parse_color) {
+        COLOR_ENABLED.store(color, Ordering::Relaxed);
+    }
+
+    if flags.contains(&CliFlag::Stats) && !stats::is_enabled() {
+        print_error_and_exit("Stats require building with the 'alloc-stats' feature");
+    }
+
+    let chosen_parts = parse_setting(&options, &config, CliOption::Part, parse_parts);
+    let cache_path = match get_setting(&options, &config, CliOption::AnswerFile) {
+        Some((path, _)) => path,
+        None => cache::DEFAULT_CACHE_PATH.to_string(),
+    };
+
+    match command {
+        Some(CliCommand::Cache) => match subcommand {
+            Some(CliSubcommand::List) => list_cache(cache_path.as_str()),
+            Some(CliSubcommand::Clear) => clear_cache(cache_path.as_str()),
+            None => print_error_and_exit("Must specify cache command: 'list' or 'clear'"),
+        },
+        Some(CliCommand::Bench) => {
+            if day.is_none() && options.contains_key(&CliOption::Input) {
+                print_error_and_exit("Must specify day to benchmark a custom input");
+            }
+            let variant_options = [CliOption::Rules, CliOption::Compartments, CliOption::GroupSize, CliOption::Reversed, CliOption::Crane];
+            if day.is_none() && variant_options.iter().any(|option| options.contains_key(option)) {
+                print_error_and_exit("Must specify day to benchmark a variant");
+            }
+            let days_to_run = match day {
+                Some(day) => vec![day],
+                None => days::IMPLEMENTED_DAYS.to_vec(),
+            };
+            let days_with_inputs = days_to_run
+                .into_iter()
+                .map(|day| (day, get_input_path(day, &options, &config), get_solve_options(day, &options)))
+                .collect();
+            let parts = match chosen_parts {
+                Some(parts) => parts,
+                None => vec![Part::Part1, Part::Part2],
+            };
+            let iterations = match options.get(&CliOption::Iterations) {
+                Some(iterations) => match iterations.parse::<usize>() {
+                    Ok(iterations) if iterations > 0 => iterations,
+                    _ => {
+                        print_error_and_exit(format!("Invalid iterations: '{}'", iterations).as_str());
+                        0
+                    }
+                },
+                None => bench::DEFAULT_ITERATIONS,
+            };
+            let history_path = match options.get(&CliOption::History) {
+                Some(path) => path.as_str(),
+                None => bench::DEFAULT_HISTORY_PATH,
+            };
+            run_benchmarks(
+                days_with_inputs,
+                parts,
+                iterations,
+                history_path,
+                flags.contains(&CliFlag::Compare),
+            );
+        }
+        None => {
+            if day.is_none() {
+                print_error_and_exit("Must specify day to run");
+            }
+            let day = day.unwrap();
+            let format = parse_setting(&options, &config, CliOption::Format, parse_format).unwrap_or(OutputFormat::Plain);
+            if let Some(top) = options.get(&CliOption::Top) {
+                if day != 1 {
+                    print_error_and_exit("Top is only supported for day 1");
+                } else if options.contains_key(&CliOption::Part) {
+                    print_error_and_exit("Top can't be combined with part");
+                }
+                match top.parse::<usize>() {
+                    Ok(count) => run_day_1_top(count, get_input_path(day, &options, &config), format),
+                    Err(_) => print_error_and_exit(format!("Invalid top: '{}'", top).as_str()),
+                }
+                return;
+            }
+            if flags.contains(&CliFlag::Animate) {
+                if day != 5 {
+                    print_error_and_exit("Animate is only supported for day 5");
+                } else if options.contains_key(&CliOption::Part) || options.contains_key(&CliOption::Mode) {
+                    print_error_and_exit("Animate can't be combined with part or mode");
+                }
+                run_day_5_animation(get_input_path(day, &options, &config), &options);
+                return;
+            }
+            if let Some(mode) = options.get(&CliOption::Mode) {
+                if options.contains_key(&CliOption::Part) {
+                    print_error_and_exit("Mode can't be combined with part");
+                }
+                run_mode(day, mode, get_input_path(day, &options, &config), &options, format);
+                return;
+            }
+            let parts = match chosen_parts {
+                Some(parts) => parts,
+                None => vec![Part::Part1],
+            };
+            // allocation stats are only meaningful if the solver actually runs
+            let use_cache = !flags.contains(&CliFlag::NoCache) && !flags.contains(&CliFlag::Stats);
+            let settings = RunSettings {
+                show_stats: flags.contains(&CliFlag::Stats),
+                cache_path: if use_cache { Some(cache_path) } else { None },
+                format,
+                jobs: parse_setting(&options, &config, CliOption::Jobs, parse_jobs).unwrap_or(1),
+                solve_options: get_solve_options(day, &options),
+            };
+            run_day(day, parts, get_input_path(day, &options, &config), &settings);
+        }
     }
 }
@@ -0,0 +1,114 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct Table {
+    headers: Vec<String>,
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(columns: &[(&str, Alignment)]) -> Table {
+        return Table {
+            headers: columns.iter().map(|(header, _)| header.to_string()).collect(),
+            alignments: columns.iter().map(|(_, alignment)| *alignment).collect(),
+            rows: vec![],
+        };
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn rows(&self) -> &[Vec<String>] {
+        return &self.rows;
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|header| header.chars().count()).collect();
+        for row in &self.rows {
+            for (index, cell) in row.iter().enumerate() {
+                if index < widths.len() {
+                    widths[index] = widths[index].max(cell.chars().count());
+                }
+            }
+        }
+
+        let mut lines = vec![self.format_text_row(&self.headers, &widths)];
+        let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        lines.push(separator.join("-+-"));
+        for row in &self.rows {
+            lines.push(self.format_text_row(row, &widths));
+        }
+        return lines.join("\n");
+    }
+
+    fn format_text_row(&self, row: &[String], widths: &[usize]) -> String {
+        let mut cells = Vec::new();
+        for (index, width) in widths.iter().enumerate() {
+            let cell = row.get(index).map(|cell| cell.as_str()).unwrap_or("");
+            let padded = match self.alignments[index] {
+                Alignment::Left => format!("{:<width$}", cell, width = width),
+                Alignment::Right => format!("{:>width$}", cell, width = width),
+            };
+            cells.push(padded);
+        }
+        return cells.join(" | ").trim_end().to_string();
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![format_csv_row(&self.headers)];
+        for row in &self.rows {
+            lines.push(format_csv_row(row));
+        }
+        return lines.join("\n");
+    }
+
+    // Every value is written as a string, keyed by its column header.
+    pub fn to_json(&self) -> String {
+        let mut objects = Vec::new();
+        for row in &self.rows {
+            let fields: Vec<String> = self
+                .headers
+                .iter()
+                .zip(row.iter())
+                .map(|(header, cell)| format!("\"{}\":\"{}\"", escape_json(header), escape_json(cell)))
+                .collect();
+            objects.push(format!("{{{}}}", fields.join(",")));
+        }
+        return format!("[{}]", objects.join(","));
+    }
+}
+
+fn format_csv_row(row: &[String]) -> String {
+    let cells: Vec<String> = row
+        .iter()
+        .map(|cell| {
+            if cell.contains(',') || cell.contains('"') || cell.contains('\n') {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        })
+        .collect();
+    return cells.join(",");
+}
+
+pub fn escape_json(text: &str) -> String {
+    let mut escaped = String::new();
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(format!("\\u{:04x}", c as u32).as_str()),
+            c => escaped.push(c),
+        }
+    }
+    return escaped;
+}
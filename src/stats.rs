@@ -0,0 +1,138 @@
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllocationStats {
+    pub allocations: usize,
+    pub bytes_allocated: usize,
+    pub peak_live_bytes: usize,
+}
+
+static PARSE_STATS: Mutex<Option<AllocationStats>> = Mutex::new(None);
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use super::AllocationStats;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+    static PEAK_LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+    // Live bytes when the counters were last reset, so that peaks are reported for one phase.
+    static BASELINE_LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+    struct CountingAllocator;
+
+    fn record_allocation(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES_ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_deallocation(size: usize) {
+        LIVE_BYTES.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let pointer = System.alloc(layout);
+            if !pointer.is_null() {
+                record_allocation(layout.size());
+            }
+            return pointer;
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let pointer = System.alloc_zeroed(layout);
+            if !pointer.is_null() {
+                record_allocation(layout.size());
+            }
+            return pointer;
+        }
+
+        unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+            System.dealloc(pointer, layout);
+            record_deallocation(layout.size());
+        }
+
+        unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_pointer = System.realloc(pointer, layout, new_size);
+            if !new_pointer.is_null() {
+                record_deallocation(layout.size());
+                record_allocation(new_size);
+            }
+            return new_pointer;
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    pub fn reset() {
+        ALLOCATIONS.store(0, Ordering::Relaxed);
+        BYTES_ALLOCATED.store(0, Ordering::Relaxed);
+        let live = LIVE_BYTES.load(Ordering::Relaxed);
+        BASELINE_LIVE_BYTES.store(live, Ordering::Relaxed);
+        PEAK_LIVE_BYTES.store(live, Ordering::Relaxed);
+    }
+
+    pub fn snapshot() -> AllocationStats {
+        return AllocationStats {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed),
+            peak_live_bytes: PEAK_LIVE_BYTES
+                .load(Ordering::Relaxed)
+                .saturating_sub(BASELINE_LIVE_BYTES.load(Ordering::Relaxed)),
+        };
+    }
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+mod counting {
+    use super::AllocationStats;
+
+    pub fn reset() {}
+
+    pub fn snapshot() -> AllocationStats {
+        return AllocationStats::default();
+    }
+}
+
+pub fn is_enabled() -> bool {
+    return cfg!(feature = "alloc-stats");
+}
+
+// Clears the counters so that the next parse phase starts from zero.
+pub fn start() {
+    if let Ok(mut parse_stats) = PARSE_STATS.lock() {
+        *parse_stats = None;
+    }
+    counting::reset();
+}
+
+// Called by solvers once their input has been parsed into a structure. Everything counted so far
+// is attributed to parsing, and counting restarts for the solve phase. Solvers that stream their
+// input, parsing while solving, don't call this, and are reported as one combined phase.
+pub fn mark_parsed() {
+    if !is_enabled() {
+        return;
+    }
+    let snapshot = counting::snapshot();
+    if let Ok(mut parse_stats) = PARSE_STATS.lock() {
+        *parse_stats = Some(snapshot);
+    }
+    counting::reset();
+}
+
+// Returns the parse phase stats, if the solver marked one, and the stats counted after it. Without
+// a parse phase, the second stats cover the whole run since the last call to `start`.
+pub fn finish() -> (Option<AllocationStats>, AllocationStats) {
+    let current = counting::snapshot();
+    let parse_stats = match PARSE_STATS.lock() {
+        Ok(mut parse_stats) => parse_stats.take(),
+        Err(_) => None,
+    };
+    return (parse_stats, current);
+}